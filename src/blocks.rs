@@ -8,24 +8,81 @@ use smallvec::SmallVec;
 use smartstring::alias::String;
 use std::collections::HashMap;
 use std::future::Future;
+use std::sync::Arc;
 use std::time::Duration;
-use tokio::sync::mpsc;
+use tokio::sync::{mpsc, Notify};
 use toml::value::Table;
 
 use crate::click::{ClickHandler, MouseButton};
 use crate::config::SharedConfig;
 use crate::errors::*;
 use crate::formatting::{value::Value, Format};
+use crate::power;
 use crate::protocol::i3bar_event::I3BarEvent;
 use crate::signals::Signal;
 use crate::widget::State;
+use crate::wrappers::Seconds;
 use crate::{Request, RequestCmd};
 
+/// Define a block's `check` function, which validates the block's config without running the
+/// block: the config is deserialized, the format templates are parsed (with their defaults) and
+/// the icons are looked up. All problems are returned, not only the first one.
+///
+/// `icons` are the constants the block passes to `set_icon` and `get_icon`, so that the checked
+/// icons can not get out of sync with the used ones. The expression may refer to the deserialized
+/// config. `actions` are the block's [`BlockAction`]s, against which the `action`s of the click
+/// config are checked.
+///
+/// ```ignore
+/// check_config!(
+///     config: CpuConfig,
+///     formats { format => DEFAULT_FORMAT },
+///     icons [ICON, ICON_BOOST_ON, ICON_BOOST_OFF],
+///     actions ACTIONS,
+/// );
+/// ```
+macro_rules! check_config {
+    (@actions) => { &[] };
+    (@actions $actions:expr) => { $actions };
+    (
+        $config:ident: $config_ty:ty
+        $(, formats { $($format:ident => $default:expr),* $(,)? })?
+        $(, icons $icons:expr)?
+        $(, actions $actions:expr)?
+        $(,)?
+    ) => {
+        /// The actions of the block, which can be bound in the click config
+        pub fn actions() -> &'static [crate::blocks::BlockAction] {
+            check_config!(@actions $($actions)?)
+        }
+
+        #[allow(unused_variables, unused_mut)]
+        pub fn check(
+            value: toml::Value,
+            shared_config: &crate::config::SharedConfig,
+        ) -> Vec<Error> {
+            use crate::formatting::config::CheckFormat;
+            let $config = match <$config_ty>::deserialize(value).config_error() {
+                Ok(config) => config,
+                Err(error) => return vec![error],
+            };
+            let mut errors = Vec::new();
+            $($(errors.extend($config.$format.check($default).err());)*)?
+            $(
+                for icon in $icons {
+                    errors.extend(shared_config.get_icon(&icon).err());
+                }
+            )?
+            errors
+        }
+    };
+}
+
 macro_rules! define_blocks {
     ($($block:ident,)*) => {
         $(pub mod $block;)*
 
-        #[derive(Deserialize, Debug, Clone, Copy)]
+        #[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
         pub enum BlockType {
             $(
                 #[allow(non_camel_case_types)]
@@ -44,6 +101,42 @@ macro_rules! define_blocks {
                     )*
                 }
             }
+
+            /// The actions of the block, which can be bound in the click config
+            pub fn actions(self) -> &'static [BlockAction] {
+                match self {
+                    $(
+                        Self::$block => $block::actions(),
+                    )*
+                }
+            }
+
+            /// Check the config of a block without running it (see `--check`). Returns all found
+            /// problems.
+            pub fn check(
+                self,
+                mut config: toml::Value,
+                shared_config: &SharedConfig,
+                id: usize,
+            ) -> Vec<Error> {
+                let common_config = match CommonConfig::new(&mut config) {
+                    Ok(common_config) => common_config,
+                    Err(error) => return vec![error.in_block(self, id)],
+                };
+                let mut errors = Vec::new();
+                let shared_config = match common_config.shared_config(shared_config) {
+                    Ok(shared_config) => shared_config,
+                    Err(error) => {
+                        errors.push(error);
+                        shared_config.clone()
+                    }
+                };
+                errors.extend(common_config.click.check_actions(self.actions()).err());
+                errors.extend(match self {
+                    $(Self::$block => $block::check(config, &shared_config),)*
+                });
+                errors.into_iter().map(|error| error.in_block(self, id)).collect()
+            }
         }
     };
 }
@@ -93,16 +186,23 @@ pub type EventsRx = mpsc::Receiver<BlockEvent>;
 
 #[derive(Debug, Clone, Copy)]
 pub enum BlockEvent {
+    /// A click that is not bound to an action
     Click(I3BarEvent),
-    Signal(Signal),
+    /// One of the actions declared with [`CommonApi::set_actions`]
+    Action(&'static str),
 }
 
+/// A named action of a block and the buttons that trigger it by default. Users can bind actions
+/// to other buttons with `action = "<name>"` in the click config.
+pub type BlockAction = (&'static str, &'static [MouseButton]);
+
 pub struct CommonApi {
     pub id: usize,
     pub shared_config: SharedConfig,
 
     pub request_sender: mpsc::Sender<Request>,
     pub cmd_buf: SmallVec<[RequestCmd; 4]>,
+    pub update_request: Arc<Notify>,
 
     pub error_interval: Duration,
     pub error_format: Option<String>,
@@ -132,13 +232,26 @@ impl CommonApi {
         receiver.await.ok().error("Failed to get events receiver")
     }
 
+    /// Declare the actions the block supports. Clicks bound to them are received as
+    /// [`BlockEvent::Action`] instead of [`BlockEvent::Click`].
+    pub fn set_actions(&mut self, actions: &'static [BlockAction]) {
+        self.cmd_buf.push(RequestCmd::SetActions(actions));
+    }
+
+    /// Receive up to `max_clicks` consecutive clicks of `button` as a single event (see
+    /// [`I3BarEvent::clicks`]). Other clicks are not delayed.
+    pub fn listen_multi_clicks(&mut self, button: MouseButton, max_clicks: u8) {
+        self.cmd_buf
+            .push(RequestCmd::ListenMultiClicks(button, max_clicks));
+    }
+
+    /// Set the widget's icon. Icons are resolved at render time, so the icon set can be changed
+    /// on config reload. Still, check that the icon exists to report errors early.
     pub fn set_icon(&mut self, icon: &str) -> Result<()> {
-        let icon = if icon.is_empty() {
-            String::new()
-        } else {
-            self.get_icon(icon)?
-        };
-        self.cmd_buf.push(RequestCmd::SetIcon(icon));
+        if !icon.is_empty() {
+            self.get_icon(icon)?;
+        }
+        self.cmd_buf.push(RequestCmd::SetIcon(icon.into()));
         Ok(())
     }
 
@@ -165,14 +278,16 @@ impl CommonApi {
     }
 
     pub fn add_button(&mut self, instance: usize, icon: &str) -> Result<()> {
+        self.get_icon(icon)?;
         self.cmd_buf
-            .push(RequestCmd::AddButton(instance, self.get_icon(icon)?));
+            .push(RequestCmd::AddButton(instance, icon.into()));
         Ok(())
     }
 
     pub fn set_button(&mut self, instance: usize, icon: &str) -> Result<()> {
+        self.get_icon(icon)?;
         self.cmd_buf
-            .push(RequestCmd::SetButton(instance, self.get_icon(icon)?));
+            .push(RequestCmd::SetButton(instance, icon.into()));
         Ok(())
     }
 
@@ -215,6 +330,19 @@ impl CommonApi {
         Ok(())
     }
 
+    /// Wait until the bar is visible again. Returns immediately if the bar is not paused.
+    /// [`Seconds::timer`] already does this, blocks that poll on their own should call it
+    /// before updating.
+    pub async fn wait_until_resumed(&self) {
+        crate::wait_until_resumed().await;
+    }
+
+    /// Wait until the block is asked to update itself (by a signal or the control interface).
+    /// Blocks should select on this alongside their update timers.
+    pub async fn wait_for_update_request(&self) {
+        self.update_request.notified().await;
+    }
+
     pub fn get_icon(&self, icon: &str) -> Result<String> {
         self.shared_config.get_icon(icon)
     }
@@ -269,9 +397,12 @@ impl CommonApi {
 
                         tokio::select! {
                             _ = tokio::time::sleep_until(retry_at) => break,
-                            Some(BlockEvent::Click(click)) = events.recv() => {
-                                if click.button == MouseButton::Left {
-                                    focused = !focused;
+                            Some(event) = events.recv() => {
+                                // Clicks bound to the block's actions toggle the error too
+                                match event {
+                                    BlockEvent::Click(click)
+                                        if click.button != MouseButton::Left => {}
+                                    _ => focused = !focused,
                                 }
                             }
                         }
@@ -295,6 +426,33 @@ pub struct CommonConfig {
     pub error_interval: u64,
     #[serde(default)]
     pub error_format: Option<String>,
+
+    /// Refresh the block when this signal is received (e.g. `4` or `"SIGRTMIN+4"`)
+    #[serde(default)]
+    pub signal: Option<Signal>,
+    /// Refresh the block when the system wakes up from sleep
+    #[serde(default = "CommonConfig::default_refresh_on_resume")]
+    pub refresh_on_resume: bool,
+    /// Multiply the update interval by this value while on battery, overrides the global one
+    #[serde(default)]
+    pub interval_on_battery: Option<f64>,
+    /// Dim the block (or switch it to `stale_state`) if it has not updated for this long
+    #[serde(default)]
+    pub stale_after: Option<Seconds>,
+    #[serde(default)]
+    pub stale_state: Option<State>,
+    /// Restart the block if it has not updated for this long
+    #[serde(default)]
+    pub stale_timeout: Option<Seconds>,
+
+    #[serde(default)]
+    pub restart: Restart,
+    #[serde(default)]
+    pub max_retries: Option<u32>,
+    #[serde(default = "CommonConfig::default_restart_delay")]
+    pub restart_delay: Seconds,
+    #[serde(default = "CommonConfig::default_max_restart_delay")]
+    pub max_restart_delay: Seconds,
 }
 
 impl CommonConfig {
@@ -302,6 +460,18 @@ impl CommonConfig {
         5
     }
 
+    fn default_refresh_on_resume() -> bool {
+        true
+    }
+
+    fn default_restart_delay() -> Seconds {
+        Seconds::new(1)
+    }
+
+    fn default_max_restart_delay() -> Seconds {
+        Seconds::new(300)
+    }
+
     pub fn new(from: &mut toml::Value) -> Result<Self> {
         const FIELDS: &[&str] = &[
             "click",
@@ -309,6 +479,16 @@ impl CommonConfig {
             "icons_format",
             "error_interval",
             "error_format",
+            "signal",
+            "refresh_on_resume",
+            "interval_on_battery",
+            "stale_after",
+            "stale_state",
+            "stale_timeout",
+            "restart",
+            "max_retries",
+            "restart_delay",
+            "max_restart_delay",
         ];
         let mut common_table = Table::new();
         if let Some(table) = from.as_table_mut() {
@@ -319,6 +499,111 @@ impl CommonConfig {
             }
         }
         let common_value: toml::Value = common_table.into();
-        CommonConfig::deserialize(common_value).config_error()
+        let common_config = CommonConfig::deserialize(common_value).config_error()?;
+        if let Some(battery_factor) = common_config.interval_on_battery {
+            power::check_battery_factor(battery_factor)?;
+        }
+        Ok(common_config)
+    }
+
+    /// Apply block's icons format and theme overrides to the global shared config
+    pub fn shared_config(&self, global: &SharedConfig) -> Result<SharedConfig> {
+        let mut shared_config = global.clone();
+        if let Some(icons_format) = &self.icons_format {
+            *Arc::make_mut(&mut shared_config.icons_format) = icons_format.clone();
+        }
+        if let Some(theme_overrides) = &self.theme_overrides {
+            Arc::make_mut(&mut shared_config.theme).apply_overrides(theme_overrides)?;
+        }
+        Ok(shared_config)
+    }
+
+    pub fn restart_policy(&self) -> RestartPolicy {
+        RestartPolicy {
+            restart: self.restart,
+            max_retries: self.max_retries,
+            delay: self.restart_delay.0,
+            max_delay: self.max_restart_delay.0,
+        }
+    }
+}
+
+/// When to restart a block which has exited
+#[derive(Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Restart {
+    Never,
+    Always,
+    OnError,
+}
+
+impl Default for Restart {
+    fn default() -> Self {
+        Self::Never
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RestartPolicy {
+    pub restart: Restart,
+    pub max_retries: Option<u32>,
+    pub delay: Duration,
+    pub max_delay: Duration,
+}
+
+impl RestartPolicy {
+    /// Whether a block should be restarted after `retries` unsuccessful restarts
+    pub fn should_restart(&self, failed: bool, retries: u32) -> bool {
+        let wanted = match self.restart {
+            Restart::Never => false,
+            Restart::Always => true,
+            Restart::OnError => failed,
+        };
+        wanted && self.max_retries.map_or(true, |max| retries < max)
+    }
+
+    /// The delay before the next restart. Doubles after every retry.
+    pub fn delay(&self, retries: u32) -> Duration {
+        self.delay
+            .checked_mul(2u32.saturating_pow(retries))
+            .unwrap_or(self.max_delay)
+            .min(self.max_delay)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn policy(restart: Restart, max_retries: Option<u32>) -> RestartPolicy {
+        RestartPolicy {
+            restart,
+            max_retries,
+            delay: Duration::from_secs(5),
+            max_delay: Duration::from_secs(60),
+        }
+    }
+
+    #[test]
+    fn should_restart() {
+        assert!(!policy(Restart::Never, None).should_restart(true, 0));
+        assert!(!policy(Restart::Never, None).should_restart(false, 0));
+        assert!(policy(Restart::OnError, None).should_restart(true, 0));
+        assert!(!policy(Restart::OnError, None).should_restart(false, 0));
+        assert!(policy(Restart::Always, None).should_restart(false, 100));
+        assert!(policy(Restart::Always, Some(3)).should_restart(false, 2));
+        assert!(!policy(Restart::Always, Some(3)).should_restart(false, 3));
+        assert!(!policy(Restart::OnError, Some(0)).should_restart(true, 0));
+    }
+
+    #[test]
+    fn delay() {
+        let policy = policy(Restart::Always, None);
+        assert_eq!(policy.delay(0), Duration::from_secs(5));
+        assert_eq!(policy.delay(1), Duration::from_secs(10));
+        assert_eq!(policy.delay(3), Duration::from_secs(40));
+        assert_eq!(policy.delay(4), Duration::from_secs(60));
+        assert_eq!(policy.delay(31), Duration::from_secs(60));
+        assert_eq!(policy.delay(u32::MAX), Duration::from_secs(60));
     }
 }
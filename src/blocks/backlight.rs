@@ -19,6 +19,7 @@
 //! `minimum` | The minimum brightness that can be scrolled down to | No | `1`
 //! `maximum` | The maximum brightness that can be scrolled up to | No | `100`
 //! `cycle` | The brightnesses to cycle through on each click | No | `[minimum, maximum]`
+//! `click_sets_brightness` | On left click, set the brightness proportionally to the click position within the block instead of cycling | No | `false`
 //! `root_scaling` | Scaling exponent reciprocal (ie. root) | No | `1.0`
 //! `invert_icons` | Invert icons' ordering, useful if you have colorful emoji | No | `false`
 //!
@@ -26,6 +27,14 @@
 //! -------------|--------------------|--------|---------------
 //! `brightness` | Current brightness | Number | %
 //!
+//! # Actions
+//!
+//! Action | Description | Default button
+//! -------|-------------|---------------
+//! `cycle` | Set the next brightness from `cycle` | Left, unless `click_sets_brightness`
+//! `brightness_up` | Increase the brightness by `step_width` | Wheel up
+//! `brightness_down` | Decrease the brightness by `step_width` | Wheel down
+//!
 //! # Example
 //!
 //! ```toml
@@ -119,17 +128,45 @@ struct BacklightConfig {
     #[derivative(Default(value = "100"))]
     maximum: u8,
     cycle: Option<Vec<u8>>,
+    click_sets_brightness: bool,
     #[derivative(Default(value = "1.0"))]
     root_scaling: f64,
     invert_icons: bool,
 }
 
+const DEFAULT_FORMAT: &str = "$brightness";
+
+const ACTIONS: &[BlockAction] = &[
+    ("cycle", &[MouseButton::Left]),
+    ("brightness_up", &[MouseButton::WheelUp]),
+    ("brightness_down", &[MouseButton::WheelDown]),
+];
+
+/// With `click_sets_brightness`, left clicks are forwarded to the block instead
+const ACTIONS_CLICK_SETS_BRIGHTNESS: &[BlockAction] = &[
+    ("cycle", &[]),
+    ("brightness_up", &[MouseButton::WheelUp]),
+    ("brightness_down", &[MouseButton::WheelDown]),
+];
+
+check_config!(
+    config: BacklightConfig,
+    formats { format => DEFAULT_FORMAT },
+    icons BACKLIGHT_ICONS,
+    actions ACTIONS,
+);
+
 pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     let mut events = api.get_events().await?;
     let dbus_conn = api.get_system_dbus_connection().await?;
 
     let config = BacklightConfig::deserialize(config).config_error()?;
-    api.set_format(config.format.with_default("$brightness")?);
+    api.set_format(config.format.with_default(DEFAULT_FORMAT)?);
+    api.set_actions(if config.click_sets_brightness {
+        ACTIONS_CLICK_SETS_BRIGHTNESS
+    } else {
+        ACTIONS
+    });
 
     let mut cycle = config
         .cycle
@@ -169,15 +206,25 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
 
         tokio::select! {
             _ = file_changes.next() => (),
-            Some(BlockEvent::Click(event)) = events.recv() => {
+            Some(event) = events.recv() => {
                 let brightness = device.brightness().await?;
-                match event.button {
-                    MouseButton::Left => {
+                match event {
+                    BlockEvent::Click(click)
+                        if click.button == MouseButton::Left && config.click_sets_brightness =>
+                    {
+                        if let Some(fraction) = click.fraction_x() {
+                            let brightness = (fraction * 100.0).round() as u8;
+                            device
+                                .set_brightness(brightness.clamp(config.minimum, config.maximum))
+                                .await?;
+                        }
+                    }
+                    BlockEvent::Action("cycle") => {
                         if let Some(brightness) = cycle.next() {
                             device.set_brightness(brightness).await?;
                         }
                     }
-                    MouseButton::WheelUp => {
+                    BlockEvent::Action("brightness_up") => {
                         device
                             .set_brightness(
                                 (brightness + config.step_width)
@@ -185,7 +232,7 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
                             )
                             .await?;
                     }
-                    MouseButton::WheelDown => {
+                    BlockEvent::Action("brightness_down") => {
                         device
                             .set_brightness(
                                 brightness
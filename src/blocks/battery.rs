@@ -24,7 +24,7 @@
 //! Placeholder  | Value                                                                   | Type              | Unit
 //! -------------|-------------------------------------------------------------------------|-------------------|-----
 //! `percentage` | Battery level, in percent                                               | String or Integer | Percents
-//! `time`       | Time remaining until (dis)charge is complete. Presented only if battery's status is (dis)charging. | String | -
+//! `time`       | Time remaining until (dis)charge is complete. Presented only if battery's status is (dis)charging. | Duration | -
 //! `power`      | Power consumption by the battery or from the power supply when charging | String or Float   | Watts
 //!
 //! # Examples
@@ -46,6 +46,14 @@
 //! allow_missing = true
 //! ```
 //!
+//! Show the remaining time as `H:MM`, if available:
+//!
+//! ```toml
+//! [block]
+//! block = "battery"
+//! format = "$percentage {$time.dur(hm)|}"
+//! ```
+//!
 //! # Icons Used
 //! - `bat_charging`
 //! - `bat_not_available`
@@ -67,19 +75,21 @@ use std::str::FromStr;
 
 use async_trait::async_trait;
 use tokio::fs::{read_dir, read_to_string};
-use tokio::time::Interval;
 use zbus::fdo::DBusProxy;
 use zbus::MessageStream;
 
 use super::prelude::*;
-use crate::util::{battery_level_icon, new_system_dbus_connection, read_file};
+use crate::util::{
+    battery_level_icon, new_system_dbus_connection, read_file, BATTERY_CHARGING_ICON,
+    BATTERY_LEVEL_ICONS,
+};
+use crate::wrappers::Timer;
 
 mod zbus_upower;
 
 /// Path for the power supply devices
 const POWER_SUPPLY_DEVICES_PATH: &str = "/sys/class/power_supply";
 
-/// Ordered list of icons used to display battery charge
 const BATTERY_UNAVAILABLE_ICON: &str = "bat_not_available";
 
 #[derive(Deserialize, Debug, Derivative)]
@@ -116,10 +126,21 @@ enum BatteryDriver {
     Upower,
 }
 
+const DEFAULT_FORMAT: &str = "$percentage";
+const DEFAULT_FULL_FORMAT: &str = "";
+
+check_config!(
+    config: BatteryConfig,
+    formats { format => DEFAULT_FORMAT, full_format => DEFAULT_FULL_FORMAT },
+    icons BATTERY_LEVEL_ICONS
+        .into_iter()
+        .chain([BATTERY_CHARGING_ICON, BATTERY_UNAVAILABLE_ICON]),
+);
+
 pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     let config = BatteryConfig::deserialize(config).config_error()?;
-    let format = config.format.with_default("$percentage")?;
-    let format_full = config.full_format.with_default("")?;
+    let format = config.format.with_default(DEFAULT_FORMAT)?;
+    let format_full = config.full_format.with_default(DEFAULT_FULL_FORMAT)?;
 
     // Get _any_ battery device if not set in the config
     let device = match config.device {
@@ -164,15 +185,12 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
                 let mut values = map!("percentage" => Value::percents(info.capacity));
                 info.power
                     .map(|p| values.insert("power".into(), Value::watts(p)));
-                info.time_remaining.map(|t| {
-                    values.insert(
-                        "time".into(),
-                        Value::text(
-                            format!("{}:{:02}", (t / 3600.) as i32, (t % 3600. / 60.) as i32)
-                                .into(),
-                        ),
-                    )
-                });
+                info.time_remaining
+                    // `Duration::from_secs_f64` panics on negative, NaN and too big values
+                    .filter(|t| (0.0..u64::MAX as f64).contains(t))
+                    .map(|t| {
+                        values.insert("time".into(), Value::duration(Duration::from_secs_f64(t)))
+                    });
                 api.set_values(values);
 
                 if info.capacity >= config.full_threshold {
@@ -225,7 +243,11 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
         }
 
         api.flush().await?;
-        device.wait_for_change().await?
+
+        tokio::select! {
+            x = device.wait_for_change() => x?,
+            _ = api.wait_for_update_request() => (),
+        }
     }
 }
 
@@ -282,7 +304,7 @@ trait BatteryDevice {
 /// <https://www.kernel.org/doc/html/v5.15/power/power_supply_class.html>
 struct PowerSupplyDevice {
     device_path: PathBuf,
-    interval: Interval,
+    interval: Timer,
 }
 
 impl PowerSupplyDevice {
@@ -24,6 +24,12 @@
 //! `available`  | Present if the device is available                                    | Flag   | -
 //! `connected`  | Present if the device is connected                                    | Flag   | -
 //!
+//! # Actions
+//!
+//! Action | Description | Default button
+//! -------|-------------|---------------
+//! `toggle_connection` | Connect or disconnect the device | Right
+//!
 //! # Examples
 //!
 //! This example just shows the icon when device is connected.
@@ -60,14 +66,28 @@ struct BluetoothConfig {
     hide_disconnected: bool,
 }
 
+const DEFAULT_FORMAT: &str = "$name{ $percentage|}|Unavailable";
+
+const ACTIONS: &[BlockAction] = &[("toggle_connection", &[MouseButton::Right])];
+
+const ICON: &str = "bluetooth";
+const ICON_HEADPHONES: &str = "headphones";
+const ICON_JOYSTICK: &str = "joystick";
+const ICON_KEYBOARD: &str = "keyboard";
+const ICON_MOUSE: &str = "mouse";
+
+check_config!(
+    config: BluetoothConfig,
+    formats { format => DEFAULT_FORMAT },
+    icons [ICON, ICON_HEADPHONES, ICON_JOYSTICK, ICON_KEYBOARD, ICON_MOUSE],
+    actions ACTIONS,
+);
+
 pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     let mut events = api.get_events().await?;
     let config = BluetoothConfig::deserialize(config).config_error()?;
-    api.set_format(
-        config
-            .format
-            .with_default("$name{ $percentage|}|Unavailable")?,
-    );
+    api.set_actions(ACTIONS);
+    api.set_format(config.format.with_default(DEFAULT_FORMAT)?);
 
     let dbus_conn = api.get_system_dbus_connection().await?;
     let mut monitor = DeviceMonitor::new(&dbus_conn, config.mac, config.adapter_mac).await?;
@@ -102,7 +122,7 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
             // Unavailable
             None => {
                 if !config.hide_disconnected {
-                    api.set_icon("bluetooth")?;
+                    api.set_icon(ICON)?;
                     api.set_state(State::Idle);
                     api.set_values(map!());
                     api.show();
@@ -116,15 +136,15 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
 
         loop {
             tokio::select! {
-                Some(BlockEvent::Click(click)) = events.recv() => {
-                    if click.button == MouseButton::Right {
-                        if let Some(dev) = monitor.device() {
-                            if let Ok(connected) = dev.connected().await {
-                                if connected {
-                                    let _ = dev.device.disconnect().await;
-                                } else {
-                                    let _ = dev.device.connect().await;
-                                }
+                Some(event) = events.recv() => {
+                    if let (BlockEvent::Action("toggle_connection"), Some(dev)) =
+                        (event, monitor.device())
+                    {
+                        if let Ok(connected) = dev.connected().await {
+                            if connected {
+                                let _ = dev.device.disconnect().await;
+                            } else {
+                                let _ = dev.device.connect().await;
                             }
                         }
                     }
@@ -349,11 +369,11 @@ impl Device {
             .icon()
             .await
             .map(|icon| match icon.as_str() {
-                "audio-card" | "audio-headset" => "headphones",
-                "input-gaming" => "joystick",
-                "input-keyboard" => "keyboard",
-                "input-mouse" => "mouse",
-                _ => "bluetooth",
+                "audio-card" | "audio-headset" => ICON_HEADPHONES,
+                "input-gaming" => ICON_JOYSTICK,
+                "input-keyboard" => ICON_KEYBOARD,
+                "input-mouse" => ICON_MOUSE,
+                _ => ICON,
             })
             .error("Failed to get icon")
     }
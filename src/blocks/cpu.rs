@@ -8,14 +8,22 @@
 //! `format_alt` | If set, block will switch between `format` and `format_alt` on every click | No | None
 //! `interval` | Update interval in seconds | No | `5`
 //!
-//! Placeholder      | Value                                                          | Type   | Unit
-//! -----------------|----------------------------------------------------------------|--------|---------------
-//! `utilization`    | Average CPU utilization                                        | Number | %
-//! `utilization<N>` | Utilization of Nth logical CPU                                 | Number | %
-//! `barchart`       | Utilization of all logical CPUs presented as a barchart        | Text   | -
-//! `frequency`      | Average CPU frequency                                          | Number | Hz
-//! `frequency<N>`   | Frequency of Nth logical CPU                                   | Number | Hz
-//! `boost`          | CPU turbo boost status (may be absent if CPU is not supported) | Text   | -
+//! Placeholder      | Value                                                          | Type           | Unit
+//! -----------------|----------------------------------------------------------------|----------------|---------------
+//! `utilization`    | Average CPU utilization                                        | Number         | %
+//! `utilization<N>` | Utilization of Nth logical CPU                                 | Number         | %
+//! `utilizations`   | Utilization of every logical CPU                               | List of Number | %
+//! `barchart`       | Utilization of all logical CPUs presented as a barchart        | Text           | -
+//! `frequency`      | Average CPU frequency                                          | Number         | Hz
+//! `frequency<N>`   | Frequency of Nth logical CPU                                   | Number         | Hz
+//! `frequencies`    | Frequency of every logical CPU                                 | List of Number | Hz
+//! `boost`          | CPU turbo boost status (may be absent if CPU is not supported) | Text           | -
+//!
+//! # Actions
+//!
+//! Action | Description | Default button
+//! -------|-------------|---------------
+//! `toggle_format` | Switch between `format` and `format_alt` | Left
 //!
 //! # Example
 //!
@@ -27,6 +35,14 @@
 //! format_alt = "$frequency.eng() \\|$boost.str()"
 //! ```
 //!
+//! Show the utilization of every core:
+//!
+//! ```toml
+//! [[block]]
+//! block = "cpu"
+//! format = "$utilizations.each({$item.eng(2)}, )"
+//! ```
+//!
 //! # Icons Used
 //! - `cpu`
 //! - `cpu_boost_on`
@@ -54,24 +70,41 @@ struct CpuConfig {
     interval: Seconds,
 }
 
+const DEFAULT_FORMAT: &str = "$utilization";
+
+const ACTIONS: &[BlockAction] = &[("toggle_format", &[MouseButton::Left])];
+
+const ICON: &str = "cpu";
+const ICON_BOOST_ON: &str = "cpu_boost_on";
+const ICON_BOOST_OFF: &str = "cpu_boost_off";
+
+check_config!(
+    config: CpuConfig,
+    formats { format => DEFAULT_FORMAT, format_alt => "" },
+    icons [ICON, ICON_BOOST_ON, ICON_BOOST_OFF],
+    actions ACTIONS,
+);
+
 pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     let mut events = api.get_events().await?;
     let config = CpuConfig::deserialize(config).config_error()?;
-    let mut format = config.format.with_default("$utilization")?;
+    api.set_actions(ACTIONS);
+    let mut format = config.format.with_default(DEFAULT_FORMAT)?;
     let mut format_alt = match config.format_alt {
         Some(f) => Some(f.with_default("")?),
         None => None,
     };
     api.set_format(format.clone());
 
-    api.set_icon("cpu")?;
-    let boost_icon_on = api.get_icon("cpu_boost_on")?;
-    let boost_icon_off = api.get_icon("cpu_boost_off")?;
+    api.set_icon(ICON)?;
+    let boost_icon_on = api.get_icon(ICON_BOOST_ON)?;
+    let boost_icon_off = api.get_icon(ICON_BOOST_OFF)?;
 
     // Store previous /proc/stat state
     let mut cputime = read_proc_stat().await?;
     let cores = cputime.1.len();
 
+    let mut timer = config.interval.timer();
     loop {
         let freqs = read_frequencies().await?;
         let freq_avg = freqs.iter().sum::<f64>() / (freqs.len() as f64);
@@ -105,6 +138,10 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
             "barchart" => Value::text(barchart),
             "frequency" => Value::hertz(freq_avg),
             "utilization" => Value::percents(utilization_avg * 100.),
+            "frequencies" => Value::list(freqs.iter().map(|f| Value::hertz(*f)).collect()),
+            "utilizations" => Value::list(
+                utilizations.iter().map(|u| Value::percents(u * 100.)).collect()
+            ),
         );
         boost.map(|b| values.insert("boost".into(), Value::Icon(b)));
         for (i, freq) in freqs.iter().enumerate() {
@@ -127,13 +164,14 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
         api.flush().await?;
 
         tokio::select! {
-            _ = sleep(config.interval.0) => (),
-            Some(BlockEvent::Click(click)) = events.recv() => {
-                if click.button == MouseButton::Left {
-                    if let Some(ref mut format_alt) = format_alt {
-                        std::mem::swap(format_alt, &mut format);
-                        api.set_format(format.clone());
-                    }
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
+            Some(event) = events.recv() => {
+                if let (BlockEvent::Action("toggle_format"), Some(format_alt)) =
+                    (event, &mut format_alt)
+                {
+                    std::mem::swap(format_alt, &mut format);
+                    api.set_format(format.clone());
                 }
             }
         }
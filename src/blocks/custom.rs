@@ -16,7 +16,6 @@
 //! `cycle` | Commands to execute and change when the button is clicked | No | None
 //! `interval` | Update interval in seconds (or "once" to update only once) | No | `10`
 //! `json` | Use JSON from command output to format the block. If the JSON is not valid, the block will error out. | No | `false`
-//! `signal` | Signal value that causes an update for this block with 0 corresponding to `-SIGRTMIN+0` and the largest value being `-SIGRTMAX` | No | None
 //! watch_files | Watch files to trigger update on file modification | No | None
 //! `hide_when_empty` | Hides the block when the command output (or json text field) is empty | No | false
 //! `shell` | Specify the shell to use when running commands | No | `$SHELL` if set, otherwise fallback to `sh`
@@ -81,15 +80,15 @@
 //! interval = "once"
 //! ```
 //!
+//! Note that `signal` is a common option available for every block.
+//!
 //! # TODO:
 //! - Use `shellexpand`
 
 use super::prelude::*;
-use crate::signals::Signal;
 use inotify::{Inotify, WatchMask};
 use std::io;
 use tokio::{process::Command, time::Instant};
-use tokio_stream::wrappers::IntervalStream;
 
 #[derive(Deserialize, Debug, Derivative)]
 #[serde(deny_unknown_fields, default)]
@@ -102,20 +101,24 @@ struct CustomConfig {
     json: bool,
     hide_when_empty: bool,
     shell: Option<StdString>,
-    signal: Option<i32>,
     watch_files: Vec<StdString>,
 }
 
+check_config!(config: CustomConfig);
+
 pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     let mut events = api.get_events().await?;
     let config = CustomConfig::deserialize(config).config_error()?;
 
     // let mut notify;
 
-    type TimerStream = Pin<Box<dyn Stream<Item = Instant> + Send + Sync>>;
+    type TimerStream = Pin<Box<dyn Stream<Item = Instant> + Send>>;
     let mut timer: TimerStream = match config.interval {
         OnceDuration::Once => Box::pin(futures::stream::pending()),
-        OnceDuration::Duration(dur) => Box::pin(IntervalStream::new(dur.timer())),
+        OnceDuration::Duration(dur) => Box::pin(futures::stream::unfold(
+            dur.timer(),
+            |mut timer| async move { Some((timer.tick().await, timer)) },
+        )),
     };
 
     type FileStream = Pin<Box<dyn Stream<Item = io::Result<inotify::EventOwned>> + Send + Sync>>;
@@ -193,13 +196,8 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
             tokio::select! {
                 _ = timer.next() => break,
                 _ = file_updates.next() => break,
-                Some(event) = events.recv() => {
-                    match (event, config.signal) {
-                        (BlockEvent::Signal(Signal::Custom(s)), Some(signal)) if s == signal => break,
-                        (BlockEvent::Click(_), _) => break,
-                        _ => (),
-                    }
-                },
+                _ = api.wait_for_update_request() => break,
+                Some(BlockEvent::Click(_)) = events.recv() => break,
             }
         }
     }
@@ -84,13 +84,42 @@ impl Block {
     }
 }
 
+check_config!(config: CustomDBusConfig);
+
 pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     let path = CustomDBusConfig::deserialize(config).config_error()?.path;
     let dbus_conn = api.get_dbus_connection().await?;
-    dbus_conn
+    let added = dbus_conn
         .object_server()
-        .at(path, Block { api })
+        .at(path.as_str(), Block { api })
         .await
         .error("Failed to setup DBus server")?;
-    Ok(())
+    if !added {
+        return Err(Error::new(format!(
+            "DBus path '{}' is already in use",
+            path
+        )));
+    }
+    // Keep the block running, so that stopping it removes the object
+    let _object = ObjectGuard { dbus_conn, path };
+    std::future::pending().await
+}
+
+/// Removes the block's object from the DBus when the block is stopped (e.g. by a config reload)
+struct ObjectGuard {
+    dbus_conn: zbus::Connection,
+    path: StdString,
+}
+
+impl Drop for ObjectGuard {
+    fn drop(&mut self) {
+        let dbus_conn = self.dbus_conn.clone();
+        let path = std::mem::take(&mut self.path);
+        tokio::spawn(async move {
+            let _ = dbus_conn
+                .object_server()
+                .remove::<Block, _>(path.as_str())
+                .await;
+        });
+    }
 }
@@ -10,7 +10,7 @@
 //! `warning` | A value which will trigger warning block state | No | `20.0`
 //! `alert` | A value which will trigger critical block state | No | `10.0`
 //! `info_type` | Determines which information will affect the block state. Possible values are `"available"`, `"free"` and `"used"` | No | `"available"`
-//! `alert_unit` | The unit of `alert` and `warning` options. If not set, percents are uesd. Possible values are `"B"`, `"KB"`, `"MB"`, `"GB"` and `"TB"` | No | None
+//! `alert_unit` | The unit of `alert` and `warning` options. If not set, percents are uesd. Possible values are `"B"`, `"KB"`, `"KiB"`, `"MB"`, `"MiB"`, `"GB"`, `"GiB"`, `"TB"` and `"TiB"` | No | None
 //!
 //! Placeholder  | Value                                                              | Type   | Unit
 //! -------------|--------------------------------------------------------------------|--------|-------
@@ -68,18 +68,28 @@ struct DiskSpaceConfig {
     alert: f64,
 }
 
+const DEFAULT_FORMAT: &str = "$available";
+
+const ICON: &str = "disk_drive";
+
+check_config!(config: DiskSpaceConfig, formats { format => DEFAULT_FORMAT }, icons [ICON]);
+
 pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     let config = DiskSpaceConfig::deserialize(config).config_error()?;
-    api.set_icon("disk_drive")?;
+    api.set_icon(ICON)?;
 
-    let format = config.format.with_default("$available")?;
+    let format = config.format.with_default(DEFAULT_FORMAT)?;
     api.set_format(format);
 
     let unit = match config.alert_unit.as_deref() {
         Some("TB") => Some(Prefix::Tera),
+        Some("TiB") => Some(Prefix::Tebi),
         Some("GB") => Some(Prefix::Giga),
+        Some("GiB") => Some(Prefix::Gibi),
         Some("MB") => Some(Prefix::Mega),
+        Some("MiB") => Some(Prefix::Mebi),
         Some("KB") => Some(Prefix::Kilo),
+        Some("KiB") => Some(Prefix::Kibi),
         Some("B") => Some(Prefix::One),
         Some(x) => return Err(Error::new(format!("Unknown unit: '{}'", x))),
         None => None,
@@ -113,13 +123,10 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
             "free" => Value::bytes(free as f64),
         ));
 
-        // Send percentage to alert check if we don't want absolute alerts
+        // Send percentage to alert check if we don't want absolute alerts. `result` is in bytes, so
+        // it is converted to `alert_unit` to be comparable with the thresholds.
         let alert_val = match unit {
-            Some(Prefix::Tera) => result * 1e12,
-            Some(Prefix::Giga) => result * 1e9,
-            Some(Prefix::Mega) => result * 1e6,
-            Some(Prefix::Kilo) => result * 1e3,
-            Some(_) => result,
+            Some(prefix) => prefix.apply(result),
             None => percentage,
         };
 
@@ -147,6 +154,9 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
 
         api.flush().await?;
 
-        timer.tick().await;
+        tokio::select! {
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
+        }
     }
 }
@@ -17,6 +17,12 @@
 //! ----|-------|------|-----
 //! `count` | Number of updates available | Number | -
 //!
+//! # Actions
+//!
+//! Action | Description | Default button
+//! -------|-------------|---------------
+//! `refresh` | Update the block | Left
+//!
 //! # Example
 //!
 //! Update the list of pending updates every thirty minutes (1800 seconds):
@@ -55,14 +61,31 @@ struct DnfConfig {
     hide_when_uptodate: bool,
 }
 
+const DEFAULT_FORMAT: &str = "$count.eng(1)";
+
+const ACTIONS: &[BlockAction] = &[("refresh", &[MouseButton::Left])];
+
+const ICON: &str = "update";
+
+check_config!(
+    config: DnfConfig,
+    formats {
+        format => DEFAULT_FORMAT,
+        format_singular => DEFAULT_FORMAT,
+        format_up_to_date => DEFAULT_FORMAT,
+    },
+    icons [ICON],
+    actions ACTIONS,
+);
+
 pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     let config = DnfConfig::deserialize(config).config_error()?;
-    let mut events = api.get_events().await?;
-    api.set_icon("update")?;
+    api.set_actions(ACTIONS);
+    api.set_icon(ICON)?;
 
-    let format = config.format.with_default("$count.eng(1)")?;
-    let format_singular = config.format_singular.with_default("$count.eng(1)")?;
-    let format_up_to_date = config.format_up_to_date.with_default("$count.eng(1)")?;
+    let format = config.format.with_default(DEFAULT_FORMAT)?;
+    let format_singular = config.format_singular.with_default(DEFAULT_FORMAT)?;
+    let format_up_to_date = config.format_up_to_date.with_default(DEFAULT_FORMAT)?;
 
     let warning_updates_regex = match config.warning_updates_regex {
         None => None,
@@ -79,6 +102,7 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
         }
     };
 
+    let mut timer = config.interval.timer();
     loop {
         let updates = get_updates_list().await?;
         let count = get_update_count(&updates);
@@ -117,15 +141,9 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
 
         api.flush().await?;
 
-        loop {
-            tokio::select! {
-                _ = sleep(config.interval.0) => break,
-                Some(BlockEvent::Click(click)) = events.recv() => {
-                    if click.button == MouseButton::Left {
-                        break;
-                    }
-                }
-            }
+        tokio::select! {
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
         }
     }
 }
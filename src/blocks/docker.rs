@@ -51,13 +51,19 @@ impl Default for DockerConfig {
     }
 }
 
+const DEFAULT_FORMAT: &str = "$running.eng(1)";
+
+const ICON: &str = "docker";
+
+check_config!(config: DockerConfig, formats { format => DEFAULT_FORMAT }, icons [ICON]);
+
 pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     let config = DockerConfig::deserialize(config).config_error()?;
     let mut timer = config.interval.timer();
     let socket_path = config.socket_path.expand()?;
 
-    api.set_format(config.format.with_default("$running.eng(1)")?);
-    api.set_icon("docker")?;
+    api.set_format(config.format.with_default(DEFAULT_FORMAT)?);
+    api.set_icon(ICON)?;
 
     loop {
         let status = api.recoverable(|| Status::new(&*socket_path), "X").await?;
@@ -69,7 +75,10 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
             "images" =>  Value::number(status.images),
         });
         api.flush().await?;
-        timer.tick().await;
+        tokio::select! {
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
+        }
     }
 }
 
@@ -82,9 +82,13 @@ impl Default for ExternalIpConfig {
     }
 }
 
+const DEFAULT_FORMAT: &str = "$ip $country_flag";
+
+check_config!(config: ExternalIpConfig, formats { format => DEFAULT_FORMAT });
+
 pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     let config = ExternalIpConfig::deserialize(config).config_error()?;
-    api.set_format(config.format.with_default("$ip $country_flag")?);
+    api.set_format(config.format.with_default(DEFAULT_FORMAT)?);
 
     type UpdatesStream = Pin<Box<dyn Stream<Item = ()>>>;
     let mut stream: UpdatesStream = if config.with_network_manager {
@@ -125,6 +129,7 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
         Box::pin(futures::stream::empty())
     };
 
+    let mut timer = config.interval.timer();
     loop {
         let info = api.recoverable(IPAddressInfo::new, "X").await?;
         let mut values = map! {
@@ -163,7 +168,8 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
         api.flush().await?;
 
         tokio::select! {
-            _ = sleep(config.interval.0) => (),
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
             _ = stream.next() => {
                 // avoid too frequent updates
                 let _ = tokio::time::timeout(Duration::from_millis(100), async {
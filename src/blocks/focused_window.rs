@@ -12,13 +12,15 @@
 //! `format` | A string to customise the output of this block. See below for available placeholders. | No | <code>"$title.rot-str(15)&vert;"</code>
 //! `autohide` | Whether to hide the block when no title is available | No | `true`
 //!
-//! Placeholder     | Value                                     | Type | Unit
-//! ----------------|-------------------------------------------|------|-----
-//! `title`         | Window's titile (may be absent)           | Text | -
-//! `marks`         | Window's marks                            | Text | -
-//! `visible_marks` | Window's marks that do not start with `_` | Text | -
+//! Placeholder         | Value                                                   | Type         | Unit
+//! --------------------|---------------------------------------------------------|--------------|-----
+//! `title`             | Window's titile (may be absent)                         | Text         | -
+//! `marks`             | Window's marks, each in brackets (e.g. `[a][b]`)        | Text         | -
+//! `visible_marks`     | Window's marks that do not start with `_`, in brackets  | Text         | -
+//! `mark_list`         | Window's marks                                          | List of Text | -
+//! `visible_mark_list` | Window's marks that do not start with `_`               | List of Text | -
 //!
-//! # Example
+//! # Examples
 //!
 //! ```toml
 //! [[block]]
@@ -27,6 +29,14 @@
 //! full = "$title.rot-str(15)"
 //! short = "$title.rot-str(10)"
 //! ```
+//!
+//! Show the marks of the window separated by commas:
+//!
+//! ```toml
+//! [[block]]
+//! block = "focused_window"
+//! format = "$visible_mark_list.join(\\, ) $title.rot-str(15)|"
+//! ```
 
 use super::prelude::*;
 use swayipc_async::{Connection, Event, EventType, WindowChange, WorkspaceChange};
@@ -47,9 +57,13 @@ impl Default for FocusedWindowConfig {
     }
 }
 
+const DEFAULT_FORMAT: &str = "$title.rot-str(15)|";
+
+check_config!(config: FocusedWindowConfig, formats { format => DEFAULT_FORMAT });
+
 pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     let config = FocusedWindowConfig::deserialize(config).config_error()?;
-    api.set_format(config.format.with_default("$title.rot-str(15)|")?);
+    api.set_format(config.format.with_default(DEFAULT_FORMAT)?);
 
     let mut title: Option<String> = None;
     let mut marks = Vec::new();
@@ -107,9 +121,23 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
 
         if updated {
             if title.is_some() || !config.autohide {
+                let visible_marks: Vec<_> = marks
+                    .iter()
+                    .filter(|m| !m.starts_with('_'))
+                    .map(StdString::as_str)
+                    .collect();
+                let all_marks: Vec<_> = marks.iter().map(StdString::as_str).collect();
+                let bracketed = |marks: &[&str]| -> Value {
+                    Value::text(marks.iter().map(|m| format!("[{}]", m)).collect())
+                };
+                let list = |marks: &[&str]| -> Value {
+                    Value::list(marks.iter().map(|&m| Value::text(m.into())).collect())
+                };
                 let mut values = map! {
-                    "marks" => Value::text(marks.iter().map(|m| format!("[{}]",m)).collect()),
-                    "visible_marks" => Value::text(marks.iter().filter(|m| !m.starts_with('_')).map(|m| format!("[{}]",m)).collect()),
+                    "marks" => bracketed(&all_marks),
+                    "visible_marks" => bracketed(&visible_marks),
+                    "mark_list" => list(&all_marks),
+                    "visible_mark_list" => list(&visible_marks),
                 };
                 title
                     .clone()
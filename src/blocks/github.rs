@@ -76,10 +76,16 @@ struct GithubConfig {
     critical: Option<Vec<String>>,
 }
 
+const DEFAULT_FORMAT: &str = "$total.eng(1)";
+
+const ICON: &str = "github";
+
+check_config!(config: GithubConfig, formats { format => DEFAULT_FORMAT }, icons [ICON]);
+
 pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     let config = GithubConfig::deserialize(config).config_error()?;
-    api.set_format(config.format.with_default("$total.eng(1)")?);
-    api.set_icon("github")?;
+    api.set_format(config.format.with_default(DEFAULT_FORMAT)?);
+    api.set_icon(ICON)?;
 
     let mut interval = config.interval.timer();
     let token = match config.token {
@@ -120,7 +126,10 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
             api.hide();
         }
         api.flush().await?;
-        interval.tick().await;
+        tokio::select! {
+            _ = interval.tick() => (),
+            _ = api.wait_for_update_request() => (),
+        }
     }
 }
 
@@ -22,6 +22,15 @@
 //! `"sct"`       | X11
 //! `"gammastep"` | X11 and Wayland
 //!
+//! # Actions
+//!
+//! Action | Description | Default button
+//! -------|-------------|---------------
+//! `set_click_temp` | Set the color temperature to `click_temp` | Left
+//! `reset` | Reset the color temperature | Right
+//! `temperature_up` | Increase the color temperature by `step` | Wheel up
+//! `temperature_down` | Decrease the color temperature by `step` | Wheel down
+//!
 //! # Example
 //!
 //! ```toml
@@ -66,9 +75,19 @@ impl Default for HueshiftConfig {
     }
 }
 
+const ACTIONS: &[BlockAction] = &[
+    ("set_click_temp", &[MouseButton::Left]),
+    ("reset", &[MouseButton::Right]),
+    ("temperature_up", &[MouseButton::WheelUp]),
+    ("temperature_down", &[MouseButton::WheelDown]),
+];
+
+check_config!(config: HueshiftConfig, actions ACTIONS);
+
 pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     let mut events = api.get_events().await?;
     let config = HueshiftConfig::deserialize(config).config_error()?;
+    api.set_actions(ACTIONS);
 
     // limit too big steps at 500K to avoid too brutal changes
     let step = config.step.max(500);
@@ -101,19 +120,21 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
 
     let mut current_temp = config.current_temp;
 
+    let mut timer = config.interval.timer();
     loop {
         api.set_text(current_temp.to_string().into());
         api.flush().await?;
 
         tokio::select! {
-            _ = sleep(config.interval.0) => (),
-            Some(BlockEvent::Click(click)) = events.recv() => {
-                match click.button {
-                    MouseButton::Left => {
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
+            Some(event) = events.recv() => {
+                match event {
+                    BlockEvent::Action("set_click_temp") => {
                         current_temp = config.click_temp;
                         driver.update(current_temp)?;
                     }
-                    MouseButton::Right => {
+                    BlockEvent::Action("reset") => {
                         if max_temp > 6500 {
                             current_temp = 6500;
                             driver.reset()?;
@@ -122,11 +143,11 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
                             driver.update(current_temp)?;
                         }
                     }
-                    MouseButton::WheelUp => {
+                    BlockEvent::Action("temperature_up") => {
                         current_temp = (current_temp + step).min(max_temp);
                         driver.update(current_temp)?;
                     }
-                    MouseButton::WheelDown => {
+                    BlockEvent::Action("temperature_down") => {
                         current_temp = current_temp.saturating_sub(step).max(min_temp);
                         driver.update(current_temp)?;
                     }
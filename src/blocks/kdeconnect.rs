@@ -57,7 +57,7 @@ use tokio::sync::mpsc;
 use zbus::dbus_proxy;
 
 use super::prelude::*;
-use crate::util::battery_level_icon;
+use crate::util::{battery_level_icon, BATTERY_CHARGING_ICON, BATTERY_LEVEL_ICONS};
 
 #[derive(Deserialize, Debug)]
 #[serde(deny_unknown_fields, default)]
@@ -85,6 +85,21 @@ impl Default for Config {
     }
 }
 
+const ICON: &str = "phone";
+const ICON_DISCONNECTED: &str = "phone_disconnected";
+const ICON_NOTIFICATION: &str = "notification";
+
+check_config!(
+    config: Config,
+    formats { format => "" },
+    icons BATTERY_LEVEL_ICONS.into_iter().chain([
+        BATTERY_CHARGING_ICON,
+        ICON,
+        ICON_DISCONNECTED,
+        ICON_NOTIFICATION,
+    ]),
+);
+
 pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     let config = Config::deserialize(config).config_error()?;
     let dbus_conn = api.get_dbus_connection().await?;
@@ -115,7 +130,7 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
                 "name" => Value::text(device.name().await?),
             };
             if connected {
-                api.set_icon("phone")?;
+                api.set_icon(ICON)?;
                 values.insert("connected".into(), Value::Flag);
 
                 let (level, charging) = device.battery().await?;
@@ -147,7 +162,7 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
                     values.insert("notif_count".into(), Value::number(notif_count));
                     values.insert(
                         "notif_icon".into(),
-                        Value::Icon(api.get_icon("notification")?.trim().into()),
+                        Value::Icon(api.get_icon(ICON_NOTIFICATION)?.trim().into()),
                     );
                 }
                 if !battery_state {
@@ -158,7 +173,7 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
                     };
                 }
             } else {
-                api.set_icon("phone_disconnected")?;
+                api.set_icon(ICON_DISCONNECTED)?;
             }
 
             api.show();
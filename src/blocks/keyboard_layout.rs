@@ -103,9 +103,13 @@ enum KeyboardLayoutDriver {
     Sway,
 }
 
+const DEFAULT_FORMAT: &str = "$layout";
+
+check_config!(config: KeyboardLayoutConfig, formats { format => DEFAULT_FORMAT });
+
 pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     let config = KeyboardLayoutConfig::deserialize(config).config_error()?;
-    api.set_format(config.format.with_default("$layout")?);
+    api.set_format(config.format.with_default(DEFAULT_FORMAT)?);
 
     let send = move |(mut layout, variant): (String, Option<String>), api: &mut CommonApi| {
         let variant = variant.unwrap_or_else(|| "N/A".into());
@@ -124,6 +128,7 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     match config.driver {
         // Just run "setxkbmap" commnad every N seconds and parse it's output
         KeyboardLayoutDriver::SetXkbMap => {
+            let mut timer = config.interval.timer();
             loop {
                 let output = Command::new("setxkbmap")
                     .arg("-query")
@@ -144,7 +149,10 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
                 send((layout.into(), None), &mut api);
                 api.flush().await?;
 
-                sleep(config.interval.0).await
+                tokio::select! {
+                    _ = timer.tick() => (),
+                    _ = api.wait_for_update_request() => (),
+                }
             }
         }
         KeyboardLayoutDriver::LocaleBus => {
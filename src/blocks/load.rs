@@ -54,10 +54,16 @@ impl Default for LoadConfig {
     }
 }
 
+const DEFAULT_FORMAT: &str = "$1m";
+
+const ICON: &str = "cogs";
+
+check_config!(config: LoadConfig, formats { format => DEFAULT_FORMAT }, icons [ICON]);
+
 pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     let config = LoadConfig::deserialize(config).config_error()?;
-    api.set_format(config.format.with_default("$1m")?);
-    api.set_icon("cogs")?;
+    api.set_format(config.format.with_default(DEFAULT_FORMAT)?);
+    api.set_icon(ICON)?;
 
     // borrowed from https://docs.rs/cpuinfo/0.1.1/src/cpuinfo/count/logical.rs.html#4-6
     let logical_cores = util::read_file(Path::new("/proc/cpuinfo"))
@@ -101,6 +107,9 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
         });
 
         api.flush().await?;
-        timer.tick().await;
+        tokio::select! {
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
+        }
     }
 }
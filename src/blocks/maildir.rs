@@ -51,9 +51,13 @@ impl Default for MaildirConfig {
     }
 }
 
+const ICON: &str = "mail";
+
+check_config!(config: MaildirConfig, icons [ICON]);
+
 pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     let config = MaildirConfig::deserialize(config).config_error()?;
-    api.set_icon("mail")?;
+    api.set_icon(ICON)?;
 
     let mut timer = config.interval.timer();
 
@@ -78,7 +82,10 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
         api.set_state(state);
         api.set_text(newmails.to_string().into());
         api.flush().await?;
-        timer.tick().await;
+        tokio::select! {
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
+        }
     }
 }
 
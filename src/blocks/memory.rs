@@ -37,6 +37,12 @@
 //! `cached`                  | Cached memory, similar to htop's yellow bar                                   | Number | Bytes
 //! `cached_percent`          | Cached memory, similar to htop's yellow bar                                   | Number | Percents
 //!
+//! # Actions
+//!
+//! Action | Description | Default button
+//! -------|-------------|---------------
+//! `toggle_type` | Switch between memory and swap | Left, if `clickable`
+//!
 //! # Example
 //!
 //! ```toml
@@ -93,27 +99,47 @@ impl Default for MemoryConfig {
     }
 }
 
+const DEFAULT_FORMAT_MEM: &str =
+    "$mem_free.eng(3,B,M)/$mem_total.eng(3,B,M)($mem_total_used_percents.eng(2))";
+const DEFAULT_FORMAT_SWAP: &str =
+    "$swap_free.eng(3,B,M)/$swap_total.eng(3,B,M)($swap_used_percents.eng(2))";
+
+const ACTIONS: &[BlockAction] = &[("toggle_type", &[MouseButton::Left])];
+
+/// Without `clickable`, clicks don't toggle the type unless the action is bound in the config
+const ACTIONS_NOT_CLICKABLE: &[BlockAction] = &[("toggle_type", &[])];
+
+const ICON_MEM: &str = "memory_mem";
+const ICON_SWAP: &str = "memory_swap";
+
+check_config!(
+    config: MemoryConfig,
+    formats { format_mem => DEFAULT_FORMAT_MEM, format_swap => DEFAULT_FORMAT_SWAP },
+    icons [ICON_MEM, ICON_SWAP],
+    actions ACTIONS,
+);
+
 pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     let mut events = api.get_events().await?;
     let config = MemoryConfig::deserialize(config).config_error()?;
-    let interval = Duration::from_secs(config.interval);
+    let mut timer = Seconds::new(config.interval).timer();
 
-    let format_mem = config.format_mem.with_default(
-        "$mem_free.eng(3,B,M)/$mem_total.eng(3,B,M)($mem_total_used_percents.eng(2))",
-    )?;
-    let format_swap = config
-        .format_swap
-        .with_default("$swap_free.eng(3,B,M)/$swap_total.eng(3,B,M)($swap_used_percents.eng(2))")?;
+    let format_mem = config.format_mem.with_default(DEFAULT_FORMAT_MEM)?;
+    let format_swap = config.format_swap.with_default(DEFAULT_FORMAT_SWAP)?;
 
-    let clickable = config.clickable;
+    api.set_actions(if config.clickable {
+        ACTIONS
+    } else {
+        ACTIONS_NOT_CLICKABLE
+    });
     let mut memtype = config.display_type;
     let mut format = match memtype {
         Memtype::Memory => {
-            api.set_icon("memory_mem")?;
+            api.set_icon(ICON_MEM)?;
             &format_mem
         }
         Memtype::Swap => {
-            api.set_icon("memory_swap")?;
+            api.set_icon(ICON_SWAP)?;
             &format_swap
         }
     };
@@ -171,24 +197,23 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
         api.flush().await?;
 
         tokio::select! {
-            _ = sleep(interval) =>(),
-            event = events.recv() => {
-                if let BlockEvent::Click(click) = event.unwrap() {
-                    if click.button == MouseButton::Left && clickable {
-                        match memtype {
-                            Memtype::Swap => {
-                                format = &format_mem;
-                                memtype = Memtype::Memory;
-                                api.set_icon("memory_mem")?;
-                            }
-                            Memtype::Memory => {
-                                format = &format_swap;
-                                memtype = Memtype::Swap;
-                                api.set_icon("memory_swap")?;
-                            }
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
+            Some(event) = events.recv() => {
+                if let BlockEvent::Action("toggle_type") = event {
+                    match memtype {
+                        Memtype::Swap => {
+                            format = &format_mem;
+                            memtype = Memtype::Memory;
+                            api.set_icon(ICON_MEM)?;
+                        }
+                        Memtype::Memory => {
+                            format = &format_swap;
+                            memtype = Memtype::Swap;
+                            api.set_icon(ICON_SWAP)?;
                         }
-                        api.set_format(format.clone());
                     }
+                    api.set_format(format.clone());
                 }
             }
         }
@@ -49,14 +49,16 @@ impl Block {
         loop {
             self.set_text(self.items[index].display.clone()).await?;
 
-            if let BlockEvent::Click(c) = self.events_receiver.recv().await.unwrap() {
-                match c.button {
-                    MouseButton::WheelUp => index += 1,
-                    MouseButton::WheelDown => index += self.items.len() + 1,
-                    MouseButton::Left => return Ok(Some(self.items[index].clone())),
-                    MouseButton::Right => return Ok(None),
-                    _ => (),
-                }
+            let c = match self.events_receiver.recv().await.unwrap() {
+                BlockEvent::Click(c) => c,
+                _ => continue,
+            };
+            match c.button {
+                MouseButton::WheelUp => index += 1,
+                MouseButton::WheelDown => index += self.items.len() + 1,
+                MouseButton::Left => return Ok(Some(self.items[index].clone())),
+                MouseButton::Right => return Ok(None),
+                _ => (),
             }
             index %= self.items.len();
         }
@@ -64,17 +66,20 @@ impl Block {
 
     async fn confirm(&mut self, msg: String) -> Result<bool> {
         self.set_text(msg).await?;
-        loop {
-            if let BlockEvent::Click(c) = self.events_receiver.recv().await.unwrap() {
-                return Ok(c.button == MouseButton::DoubleLeft);
-            }
-        }
+        Ok(matches!(
+            self.events_receiver.recv().await.unwrap(),
+            BlockEvent::Click(c) if c.button == MouseButton::Left && c.clicks == 2
+        ))
     }
 }
 
+check_config!(config: Config);
+
 pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     let events = api.get_events().await?;
     let config = Config::deserialize(config).config_error()?;
+    // Confirmation requires a double click
+    api.listen_multi_clicks(MouseButton::Left, 2);
 
     let mut block = Block {
         api,
@@ -45,12 +45,32 @@ struct OwnerChange {
     pub new_owner: Optional<StdString>,
 }
 
+const DEFAULT_FORMAT: &str = "$title_artist.rot-str()|";
+
+const ACTIONS: &[BlockAction] = &[
+    ("play_pause", &[]),
+    ("next", &[]),
+    ("prev", &[]),
+    ("prev_player", &[MouseButton::WheelUp]),
+    ("next_player", &[MouseButton::WheelDown]),
+];
+
+const ICON: &str = "music";
+
+check_config!(
+    config: MusicConfig,
+    formats { format => DEFAULT_FORMAT },
+    icons [ICON],
+    actions ACTIONS,
+);
+
 pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     let dbus_conn = new_dbus_connection().await?;
     let mut events = api.get_events().await?;
     let config = MusicConfig::deserialize(config).config_error()?;
-    api.set_format(config.format.with_default("$title_artist.rot-str()|")?);
-    api.set_icon("music")?;
+    api.set_actions(ACTIONS);
+    api.set_format(config.format.with_default(DEFAULT_FORMAT)?);
+    api.set_icon(ICON)?;
 
     // Init buttons
     for button in &config.buttons {
@@ -184,9 +204,9 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
                 }
             }
             // Wait for a click
-            Some(BlockEvent::Click(click)) = events.recv() => {
-                match click.button {
-                    MouseButton::Left => {
+            Some(event) = events.recv() => {
+                match event {
+                    BlockEvent::Click(click) if click.button == MouseButton::Left => {
                         if let Some(ref player) = player {
                             match click.instance {
                                 Some(PLAY_PAUSE_BTN) => player.play_pause().await?,
@@ -196,14 +216,23 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
                             }
                         }
                     }
-                    MouseButton::WheelUp => {
+                    BlockEvent::Action(action @ ("play_pause" | "next" | "prev")) => {
+                        if let Some(ref player) = player {
+                            match action {
+                                "play_pause" => player.play_pause().await?,
+                                "next" => player.next().await?,
+                                _ => player.prev().await?,
+                            }
+                        }
+                    }
+                    BlockEvent::Action("prev_player") => {
                         if let Some(cur) = cur_player {
                             if cur > 0 {
                                 cur_player = Some(cur - 1);
                             }
                         }
                     }
-                    MouseButton::WheelDown => {
+                    BlockEvent::Action("next_player") => {
                         if let Some(cur) = cur_player {
                             if cur + 1 < players.len() {
                                 cur_player = Some(cur + 1);
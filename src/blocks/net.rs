@@ -22,6 +22,12 @@
 //! `frequency`  | WiFi frequency           | Number | Hz
 //! `signal`     | WiFi signal              | Number | %
 //!
+//! # Actions
+//!
+//! Action | Description | Default button
+//! -------|-------------|---------------
+//! `toggle_format` | Switch between `format` and `format_alt` | Left
+//!
 //! # Example
 //!
 //! Display WiFi info if available
@@ -41,7 +47,7 @@
 //! - `net_down`
 
 use super::prelude::*;
-use crate::netlink::{default_interface, NetDevice};
+use crate::netlink::{default_interface, NetDevice, DEVICE_ICONS};
 use crate::util;
 use std::time::Instant;
 
@@ -56,12 +62,25 @@ struct NetConfig {
     device: Option<String>,
 }
 
+const DEFAULT_FORMAT: &str = "$speed_down.eng(3,B,K)$speed_up.eng(3,B,K)";
+
+const ACTIONS: &[BlockAction] = &[("toggle_format", &[MouseButton::Left])];
+
+const ICON_UP: &str = "net_up";
+const ICON_DOWN: &str = "net_down";
+
+check_config!(
+    config: NetConfig,
+    formats { format => DEFAULT_FORMAT, format_alt => "" },
+    icons [ICON_UP, ICON_DOWN].into_iter().chain(DEVICE_ICONS),
+    actions ACTIONS,
+);
+
 pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     let mut events = api.get_events().await?;
     let config = NetConfig::deserialize(config).config_error()?;
-    let mut format = config
-        .format
-        .with_default("$speed_down.eng(3,B,K)$speed_up.eng(3,B,K)")?;
+    api.set_actions(ACTIONS);
+    let mut format = config.format.with_default(DEFAULT_FORMAT)?;
     let mut format_alt = match config.format_alt {
         Some(f) => Some(f.with_default("")?),
         None => None,
@@ -74,6 +93,7 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     let mut tx_hist = [0f64; 8];
     let mut rx_hist = [0f64; 8];
 
+    let mut interval = config.interval.timer();
     loop {
         let mut speed_down: f64 = 0.0;
         let mut speed_up: f64 = 0.0;
@@ -112,8 +132,8 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
         let wifi = device.wifi_info()?;
 
         let mut values = map! {
-            "speed_down" => Value::bytes(speed_down).icon(api.get_icon("net_down")?),
-            "speed_up" => Value::bytes(speed_up).icon(api.get_icon("net_up")?),
+            "speed_down" => Value::bytes(speed_down).icon(api.get_icon(ICON_DOWN)?),
+            "speed_up" => Value::bytes(speed_up).icon(api.get_icon(ICON_UP)?),
             "graph_down" => Value::text(util::format_vec_to_bar_graph(&rx_hist)),
             "graph_up" => Value::text(util::format_vec_to_bar_graph(&tx_hist)),
             "device" => Value::text(device.interface.into()),
@@ -130,13 +150,14 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
         api.flush().await?;
 
         tokio::select! {
-            _ = sleep(config.interval.0) =>(),
-            Some(BlockEvent::Click(click)) = events.recv() => {
-                if click.button == MouseButton::Left {
-                    if let Some(ref mut format_alt) = format_alt {
-                        std::mem::swap(format_alt, &mut format);
-                        api.set_format(format.clone());
-                    }
+            _ = interval.tick() => (),
+            _ = api.wait_for_update_request() => (),
+            Some(event) = events.recv() => {
+                if let (BlockEvent::Action("toggle_format"), Some(format_alt)) =
+                    (event, &mut format_alt)
+                {
+                    std::mem::swap(format_alt, &mut format);
+                    api.set_format(format.clone());
                 }
             }
         }
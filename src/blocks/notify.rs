@@ -15,6 +15,12 @@
 //! ------------|--------------------------------------------|--------|-----
 //! `paused`    | Present only if notifications are disabled | Flag   | -
 //!
+//! # Actions
+//!
+//! Action | Description | Default button
+//! -------|-------------|---------------
+//! `toggle_paused` | Pause or resume notifications | Left
+//!
 //! # Examples
 //!
 //! How to use `paused` flag
@@ -57,9 +63,19 @@ impl Default for DriverType {
     }
 }
 
+const ACTIONS: &[BlockAction] = &[("toggle_paused", &[MouseButton::Left])];
+
+check_config!(
+    config: NotifyConfig,
+    formats { format => "" },
+    icons [ICON_ON, ICON_OFF],
+    actions ACTIONS,
+);
+
 pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     let mut events = api.get_events().await?;
     let config = NotifyConfig::deserialize(config).config_error()?;
+    api.set_actions(ACTIONS);
     api.set_format(config.format.with_default("")?);
 
     let dbus_conn = api.get_dbus_connection().await?;
@@ -86,10 +102,8 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
                     break;
                 }
                 event = events.recv() => {
-                    if let BlockEvent::Click(click) = event.unwrap() {
-                        if click.button == MouseButton::Left {
-                            driver.set_paused(!is_paused).await?;
-                        }
+                    if let BlockEvent::Action("toggle_paused") = event.unwrap() {
+                        driver.set_paused(!is_paused).await?;
                     }
                 }
             }
@@ -17,6 +17,12 @@
 //! `name` | Label to show before the mail count. | No | None
 //! `interval` | Update interval in seconds. | No | `10`
 //!
+//! # Actions
+//!
+//! Action | Description | Default button
+//! -------|-------------|---------------
+//! `refresh` | Update the block | Left
+//!
 //! # Example
 //!
 //! ```toml
@@ -61,13 +67,20 @@ impl Default for NotmuchConfig {
     }
 }
 
+const ACTIONS: &[BlockAction] = &[("refresh", &[MouseButton::Left])];
+
+const ICON: &str = "mail";
+
+check_config!(config: NotmuchConfig, icons [ICON], actions ACTIONS);
+
 pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
-    let mut events = api.get_events().await?;
     let config = NotmuchConfig::deserialize(config).config_error()?;
-    api.set_icon("mail")?;
+    api.set_actions(ACTIONS);
+    api.set_icon(ICON)?;
 
     let db = config.maildir.expand()?;
 
+    let mut timer = config.interval.timer();
     loop {
         // TODO: spawn_blocking
         let count = run_query(&db, &config.query).error("Failed to get count")?;
@@ -92,15 +105,9 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
 
         api.flush().await?;
 
-        loop {
-            tokio::select! {
-                _ = sleep(config.interval.0) => break,
-                Some(BlockEvent::Click(click)) = events.recv() => {
-                    if click.button == MouseButton::Left {
-                        break;
-                    }
-                }
-            }
+        tokio::select! {
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
         }
     }
 }
@@ -37,6 +37,12 @@
 //! `aur`   | Number of updates available according to `<aur_command>` | Number | -
 //! `both`  | Cumulative number of updates available according to `pacman` and `<aur_command>` | Number | -
 //!
+//! # Actions
+//!
+//! Action | Description | Default button
+//! -------|-------------|---------------
+//! `refresh` | Update the block | Left
+//!
 //! # Examples
 //!
 //! Update the list of pending updates every ten minutes (600 seconds):
@@ -120,14 +126,31 @@ struct PacmanConfig {
     hide_when_uptodate: bool,
 }
 
+const DEFAULT_FORMAT: &str = "$pacman.eng(1)";
+
+const ACTIONS: &[BlockAction] = &[("refresh", &[MouseButton::Left])];
+
+const ICON: &str = "update";
+
+check_config!(
+    config: PacmanConfig,
+    formats {
+        format => DEFAULT_FORMAT,
+        format_singular => DEFAULT_FORMAT,
+        format_up_to_date => DEFAULT_FORMAT,
+    },
+    icons [ICON],
+    actions ACTIONS,
+);
+
 pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     let config = PacmanConfig::deserialize(config).config_error()?;
-    let mut events = api.get_events().await?;
-    api.set_icon("update")?;
+    api.set_actions(ACTIONS);
+    api.set_icon(ICON)?;
 
-    let format = config.format.with_default("$pacman.eng(1)")?;
-    let format_singular = config.format_singular.with_default("$pacman.eng(1)")?;
-    let format_up_to_date = config.format_up_to_date.with_default("$pacman.eng(1)")?;
+    let format = config.format.with_default(DEFAULT_FORMAT)?;
+    let format_singular = config.format_singular.with_default(DEFAULT_FORMAT)?;
+    let format_up_to_date = config.format_up_to_date.with_default(DEFAULT_FORMAT)?;
 
     macro_rules! any_format_contains {
         ($name:expr) => {
@@ -176,6 +199,7 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
         }
     };
 
+    let mut timer = config.interval.timer();
     loop {
         let (values, warning, critical, total) = match &watched {
             Watched::Pacman => {
@@ -265,15 +289,9 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
 
         api.flush().await?;
 
-        loop {
-            tokio::select! {
-                _ = sleep(config.interval.0) => break,
-                Some(BlockEvent::Click(click)) = events.recv() => {
-                    if click.button == MouseButton::Left {
-                        break;
-                    }
-                }
-            }
+        tokio::select! {
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
         }
     }
 }
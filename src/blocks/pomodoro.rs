@@ -19,6 +19,14 @@
 //! `break_message` | Message when break is over | No | `Break over! Time to work!`
 //! `notify_cmd` | A shell command to run as a notifier. `{msg}` will be substituted with either `message` or `break_message`. | No | `swaynag -m '{msg}'`
 //! `blocking_cmd` | Is `notify_cmd` blocking? If it is, then pomodoro block will wait until the command finishes before proceeding. Otherwise, you will have to click on the block in order to proceed. | No | `true`
+//! `format` | A string to customise the output of this block while working. See below for available placeholders. | No | <code>"{$progress &vert;}$time_remaining.dur(min)"</code>
+//! `break_format` | Same as `format` but for breaks | No | `"Break: $time_remaining.dur(min)"`
+//!
+//! Placeholder      | Value                                                          | Type     | Unit
+//! -----------------|----------------------------------------------------------------|----------|-----
+//! `time_remaining` | Time left until the end of the current pomodoro or break       | Duration | -
+//! `completed`      | The number of completed pomodoros                              | Number   | -
+//! `progress`       | A `\|` for every completed pomodoro. Absent if there are none. | Text     | -
 //!
 //! # Example
 //!
@@ -41,9 +49,9 @@
 //!
 //! # TODO
 //! - Use different icons.
-//! - Use format strings.
 
 use super::prelude::*;
+use crate::formatting::Format;
 use crate::subprocess::{spawn_shell, spawn_shell_sync};
 use std::time::Instant;
 use tokio::sync::mpsc;
@@ -55,6 +63,8 @@ struct PomodoroConfig {
     break_message: String,
     notify_cmd: Option<String>,
     blocking_cmd: bool,
+    format: FormatConfig,
+    break_format: FormatConfig,
 }
 
 impl Default for PomodoroConfig {
@@ -64,6 +74,8 @@ impl Default for PomodoroConfig {
             break_message: "Break over! Time to work!".into(),
             notify_cmd: Some("swaynag -m '{msg}'".into()),
             blocking_cmd: true,
+            format: FormatConfig::default(),
+            break_format: FormatConfig::default(),
         }
     }
 }
@@ -71,6 +83,8 @@ impl Default for PomodoroConfig {
 struct Block {
     api: CommonApi,
     block_config: PomodoroConfig,
+    format: Format,
+    break_format: Format,
     events_receiver: mpsc::Receiver<BlockEvent>,
 }
 
@@ -80,6 +94,27 @@ impl Block {
         self.api.flush().await
     }
 
+    async fn set_time_remaining(
+        &mut self,
+        format: Format,
+        left: Duration,
+        completed: u64,
+    ) -> Result<()> {
+        let mut values = map! {
+            "time_remaining" => Value::duration(left),
+            "completed" => Value::number(completed),
+        };
+        if completed > 0 {
+            values.insert(
+                "progress".into(),
+                Value::text("|".repeat(completed as usize).into()),
+            );
+        }
+        self.api.set_format(format);
+        self.api.set_values(values);
+        self.api.flush().await
+    }
+
     async fn wait_for_click(&mut self, button: MouseButton) {
         loop {
             if let Some(BlockEvent::Click(click)) = self.events_receiver.recv().await {
@@ -132,16 +167,8 @@ impl Block {
                     break;
                 }
                 let left = task_len - elapsed;
-                let text = if pomodoro == 0 {
-                    format!("{} min", (left.as_secs() + 59) / 60,)
-                } else {
-                    format!(
-                        "{} {} min",
-                        "|".repeat(pomodoro as usize),
-                        (left.as_secs() + 59) / 60,
-                    )
-                };
-                self.set_text(text.into()).await?;
+                self.set_time_remaining(self.format.clone(), left, pomodoro)
+                    .await?;
                 tokio::select! {
                     _ = sleep(Duration::from_secs(10)) => (),
                     Some(BlockEvent::Click(click)) = self.events_receiver.recv() => {
@@ -182,7 +209,7 @@ impl Block {
                     break;
                 }
                 let left = break_len - elapsed;
-                self.set_text(format!("Break: {} min", (left.as_secs() + 59) / 60,).into())
+                self.set_time_remaining(self.break_format.clone(), left, pomodoro + 1)
                     .await?;
                 tokio::select! {
                     _ = sleep(Duration::from_secs(10)) => (),
@@ -217,13 +244,29 @@ impl Block {
     }
 }
 
+const DEFAULT_FORMAT: &str = "{$progress |}$time_remaining.dur(min)";
+const DEFAULT_BREAK_FORMAT: &str = "Break: $time_remaining.dur(min)";
+
+const ICON: &str = "pomodoro";
+
+check_config!(
+    config: PomodoroConfig,
+    formats { format => DEFAULT_FORMAT, break_format => DEFAULT_BREAK_FORMAT },
+    icons [ICON],
+);
+
 pub async fn run(block_config: toml::Value, mut api: CommonApi) -> Result<()> {
     let events = api.get_events().await?;
-    let block_config = PomodoroConfig::deserialize(block_config).config_error()?;
-    api.set_icon("pomodoro")?;
+    let mut block_config = PomodoroConfig::deserialize(block_config).config_error()?;
+    api.set_icon(ICON)?;
+    let format = std::mem::take(&mut block_config.format).with_default(DEFAULT_FORMAT)?;
+    let break_format =
+        std::mem::take(&mut block_config.break_format).with_default(DEFAULT_BREAK_FORMAT)?;
     let mut block = Block {
         api,
         block_config,
+        format,
+        break_format,
         events_receiver: events,
     };
 
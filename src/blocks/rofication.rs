@@ -14,6 +14,12 @@
 //! -----|-------|------|-----
 //! `num` | Number of pending notifications | Number | -
 //!
+//! # Actions
+//!
+//! Action | Description | Default button
+//! -------|-------------|---------------
+//! `show_notifications` | Open `rofication-gui` | Left
+//!
 //! # Example
 //!
 //! ```toml
@@ -48,14 +54,29 @@ impl Default for RoficationConfig {
     }
 }
 
+const DEFAULT_FORMAT: &str = "$num.eng(1)";
+
+const ACTIONS: &[BlockAction] = &[("show_notifications", &[MouseButton::Left])];
+
+const ICON: &str = "bell";
+
+check_config!(
+    config: RoficationConfig,
+    formats { format => DEFAULT_FORMAT },
+    icons [ICON],
+    actions ACTIONS,
+);
+
 pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     let mut events = api.get_events().await?;
     let config = RoficationConfig::deserialize(config).config_error()?;
-    api.set_format(config.format.with_default("$num.eng(1)")?);
-    api.set_icon("bell")?;
+    api.set_actions(ACTIONS);
+    api.set_format(config.format.with_default(DEFAULT_FORMAT)?);
+    api.set_icon(ICON)?;
 
     let path = config.socket_path.expand()?;
 
+    let mut timer = config.interval.timer();
     loop {
         let (num, crit) = api.recoverable(|| rofication_status(&path), "X").await?;
 
@@ -72,9 +93,10 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
 
         loop {
             tokio::select! {
-                _ = sleep(config.interval.0) => break,
-                Some(BlockEvent::Click(click)) = events.recv() => {
-                    if click.button == MouseButton::Left {
+                _ = timer.tick() => break,
+                _ = api.wait_for_update_request() => break,
+                Some(event) = events.recv() => {
+                    if let BlockEvent::Action("show_notifications") = event {
                         let _ = spawn_shell("rofication-gui");
                         break;
                     }
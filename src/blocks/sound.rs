@@ -43,6 +43,7 @@
 //! `natural_mapping` | When using the ALSA driver, display the "mapped volume" as given by `alsamixer`/`amixer -M`, which represents the volume level more naturally with respect for the human ear. | No | `false`
 //! `step_width` | The percent volume level is increased/decreased for the selected audio device when scrolling. Capped automatically at 50. | No | `5`
 //! `max_vol` | Max volume in percent that can be set via scrolling. Note it can still be set above this value if changed by another application. | No | `None`
+//! `click_sets_volume` | On left click, set the volume proportionally to the click position within the block (up to `max_vol` or 100%) | No | `false`
 //! `on_click` | Shell command to run when the sound block is clicked. | No | None
 //! `show_volume_when_muted` | Show the volume even if it is currently muted. | No | `false`
 //! `headphones_indicator` | Change icon when headphones are plugged in (pulseaudio only) | No | `false`
@@ -53,6 +54,14 @@
 //! `output_name` | PulseAudio or ALSA device name | Text | -
 //! `output_description` | PulseAudio device description, will fallback to `output_name` if no description is available and will be overwritten by mappings (mappings will still use `output_name`) | Text | -
 //!
+//! # Actions
+//!
+//! Action | Description | Default button
+//! -------|-------------|---------------
+//! `toggle_mute` | Mute or unmute the device | Right
+//! `volume_up` | Increase the volume by `step_width` | Wheel up
+//! `volume_down` | Decrease the volume by `step_width` | Wheel down
+//!
 //! #  Icons Used
 //!
 //! - `microphone_muted`
@@ -109,34 +118,66 @@ struct SoundConfig {
     show_volume_when_muted: bool,
     mappings: Option<HashMap<String, String>>,
     max_vol: Option<u32>,
+    click_sets_volume: bool,
 }
 
+const DEFAULT_FORMAT: &str = "$volume.eng(2)|";
+
+const ACTIONS: &[BlockAction] = &[
+    ("toggle_mute", &[MouseButton::Right]),
+    ("volume_up", &[MouseButton::WheelUp]),
+    ("volume_down", &[MouseButton::WheelDown]),
+];
+
+/// Icons for a muted device and for low, medium and high volumes
+const SINK_ICONS: [&str; 4] = ["volume_muted", "volume_empty", "volume_half", "volume_full"];
+const SOURCE_ICONS: [&str; 4] = [
+    "microphone_muted",
+    "microphone_empty",
+    "microphone_half",
+    "microphone_full",
+];
+const ICON_HEADPHONES: &str = "headphones";
+
+check_config!(
+    config: SoundConfig,
+    formats { format => DEFAULT_FORMAT },
+    icons match config.device_kind {
+        DeviceKind::Sink => SINK_ICONS,
+        DeviceKind::Source => SOURCE_ICONS,
+    }
+    .into_iter()
+    .chain(
+        if config.headphones_indicator && config.device_kind == DeviceKind::Sink {
+            Some(ICON_HEADPHONES)
+        } else {
+            None
+        },
+    ),
+    actions ACTIONS,
+);
+
 pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     let mut events = api.get_events().await?;
     let config = SoundConfig::deserialize(config).config_error()?;
-    api.set_format(config.format.with_default("$volume.eng(2)|")?);
+    api.set_actions(ACTIONS);
+    api.set_format(config.format.with_default(DEFAULT_FORMAT)?);
 
     let device_kind = config.device_kind;
-    let icon = |volume: u32, headphones: bool| -> String {
+    let icon = |volume: u32, headphones: bool| -> &'static str {
         if config.headphones_indicator && headphones && config.device_kind == DeviceKind::Sink {
-            "headphones".into()
+            ICON_HEADPHONES
         } else {
-            let mut icon = String::new();
-            let _ = write!(
-                icon,
-                "{}_{}",
-                match device_kind {
-                    DeviceKind::Source => "microphone",
-                    DeviceKind::Sink => "volume",
-                },
-                match volume {
-                    0 => "muted",
-                    1..=20 => "empty",
-                    21..=70 => "half",
-                    _ => "full",
-                }
-            );
-            icon
+            let icons = match device_kind {
+                DeviceKind::Source => SOURCE_ICONS,
+                DeviceKind::Sink => SINK_ICONS,
+            };
+            icons[match volume {
+                0 => 0,
+                1..=20 => 1,
+                21..=70 => 2,
+                _ => 3,
+            }]
         }
     };
 
@@ -195,13 +236,13 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
         };
 
         if device.muted() {
-            api.set_icon(&icon(0, headphones))?;
+            api.set_icon(icon(0, headphones))?;
             api.set_state(State::Warning);
             if !config.show_volume_when_muted {
                 values.remove("volume");
             }
         } else {
-            api.set_icon(&icon(volume, headphones))?;
+            api.set_icon(icon(volume, headphones))?;
             api.set_state(State::Idle);
         }
 
@@ -210,15 +251,25 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
 
         tokio::select! {
             val = device.wait_for_update() => val?,
-            Some(BlockEvent::Click(click)) = events.recv() => {
-                match click.button {
-                    MouseButton::Right => {
+            Some(event) = events.recv() => {
+                match event {
+                    BlockEvent::Click(click)
+                        if click.button == MouseButton::Left && config.click_sets_volume =>
+                    {
+                        if let Some(fraction) = click.fraction_x() {
+                            let max_vol = config.max_vol.unwrap_or(100);
+                            let volume = (fraction * max_vol as f64).round() as i32;
+                            let step = volume - device.volume() as i32;
+                            device.set_volume(step, config.max_vol).await?;
+                        }
+                    }
+                    BlockEvent::Action("toggle_mute") => {
                         device.toggle().await?;
                     }
-                    MouseButton::WheelUp => {
+                    BlockEvent::Action("volume_up") => {
                         device.set_volume(step_width, config.max_vol).await?;
                     }
-                    MouseButton::WheelDown => {
+                    BlockEvent::Action("volume_down") => {
                         device.set_volume(-step_width, config.max_vol).await?;
                     }
                     _ => ()
@@ -50,17 +50,30 @@ impl Default for SpeedtestConfig {
     }
 }
 
+const DEFAULT_FORMAT: &str = "$ping$speed_down$speed_up";
+
+const ICON_PING: &str = "ping";
+const ICON_DOWN: &str = "net_down";
+const ICON_UP: &str = "net_up";
+
+check_config!(
+    config: SpeedtestConfig,
+    formats { format => DEFAULT_FORMAT },
+    icons [ICON_PING, ICON_DOWN, ICON_UP],
+);
+
 pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     let config = SpeedtestConfig::deserialize(config).config_error()?;
-    api.set_format(config.format.with_default("$ping$speed_down$speed_up")?);
+    api.set_format(config.format.with_default(DEFAULT_FORMAT)?);
 
-    let icon_ping = api.get_icon("ping")?;
-    let icon_down = api.get_icon("net_down")?;
-    let icon_up = api.get_icon("net_up")?;
+    let icon_ping = api.get_icon(ICON_PING)?;
+    let icon_down = api.get_icon(ICON_DOWN)?;
+    let icon_up = api.get_icon(ICON_UP)?;
 
     let mut command = Command::new("speedtest-cli");
     command.arg("--json");
 
+    let mut timer = config.interval.timer();
     loop {
         let output = command
             .output()
@@ -78,7 +91,10 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
             "speed_up" => Value::bits(output.upload).icon(icon_up.clone()),
         });
         api.flush().await?;
-        sleep(config.interval.0).await;
+        tokio::select! {
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
+        }
     }
 }
 
@@ -21,6 +21,13 @@
 //! `done`        | Present only if `count` is zero             | Flag   | -
 //! `single`      | Present only if `count` is one              | Flag   | -
 //!
+//! # Actions
+//!
+//! Action | Description | Default button
+//! -------|-------------|---------------
+//! `refresh` | Update the number of tasks | Left
+//! `next_filter` | Cycle through the user's filters | Right
+//!
 //! # Example
 //!
 //! In this example, block will display "All done" if `count` is zero, "One task" if `count` is one
@@ -30,7 +37,7 @@
 //! [[block]]
 //! block = "taskwarrior"
 //! interval = 60
-//! format = "$done{All done}|$single{One task}|Tasks: $count.eng(1)"
+//! format = "${$count == 0}{All done}|${$count == 1}{One task}|Tasks: $count.eng(1)"
 //! warning_threshold = 10
 //! critical_threshold = 20
 //! [[block.filters]]
@@ -77,11 +84,28 @@ impl Default for TaskwarriorConfig {
     }
 }
 
+const DEFAULT_FORMAT: &str = "$done|$count.eng(1)";
+
+const ACTIONS: &[BlockAction] = &[
+    ("refresh", &[MouseButton::Left]),
+    ("next_filter", &[MouseButton::Right]),
+];
+
+const ICON: &str = "tasks";
+
+check_config!(
+    config: TaskwarriorConfig,
+    formats { format => DEFAULT_FORMAT },
+    icons [ICON],
+    actions ACTIONS,
+);
+
 pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     let mut events = api.get_events().await?;
     let config = TaskwarriorConfig::deserialize(config).config_error()?;
-    api.set_format(config.format.with_default("$done|$count.eng(1)")?);
-    api.set_icon("tasks")?;
+    api.set_actions(ACTIONS);
+    api.set_format(config.format.with_default(DEFAULT_FORMAT)?);
+    api.set_icon(ICON)?;
 
     let mut filters = config.filters.iter().cycle();
     let mut filter = filters.next().error("failed to get next filter")?;
@@ -95,6 +119,7 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
         .event_stream(&mut buffer)
         .error("Failed to create event stream")?;
 
+    let mut timer = config.interval.timer();
     loop {
         let number_of_tasks = get_number_of_tasks(&filter.filter).await?;
 
@@ -126,10 +151,11 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
         api.flush().await?;
 
         tokio::select! {
-            _ = sleep(config.interval.0) =>(),
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
             _ = updates.next() => (),
-            Some(BlockEvent::Click(click)) = events.recv() => {
-                if click.button == MouseButton::Right {
+            Some(event) = events.recv() => {
+                if let BlockEvent::Action("next_filter") = event {
                     filter = filters.next().error("failed to get next filter")?;
                 }
             }
@@ -21,38 +21,55 @@
 //! `format` | A string to customise the output of this block. See below for available placeholders | No | `"$average avg, $max max|"`
 //! `interval` | Update interval in seconds | No | `5`
 //! `collapsed` | Whether the block will be collapsed by default | No | `false`
-//! `scale` | Either `"celsius"` or `"fahrenheit"` | No | `"celsius"`
 //! `good` | Maximum temperature to set state to good | No | `20` °C (`68` °F)
 //! `idle` | Maximum temperature to set state to idle | No | `45` °C (`113` °F)
 //! `info` | Maximum temperature to set state to info | No | `60` °C (`140` °F)
 //! `warning` | Maximum temperature to set state to warning. Beyond this temperature, state is set to critical | No | `80` °C (`176` °F)
+//! `scale` | Deprecated, use the unit argument of the formatter instead. Either `"celsius"` or `"fahrenheit"`. Sets the unit of the placeholders and of the thresholds above. | No | `"celsius"`
 //! `chip` | Narrows the results to a given chip name. `*` may be used as a wildcard. | No | None
 //! `inputs` | Narrows the results to individual inputs reported by each chip. | No | None
 //!
-//! Placeholder | Value                                | Type   | Unit
-//! ------------|--------------------------------------|--------|--------
-//! `min`       | Minimum temperature among all inputs | Number | Degrees
-//! `average`   | Average temperature among all inputs | Number | Degrees
-//! `max`       | Maximum temperature among all inputs | Number | Degrees
+//! Placeholder | Value                                | Type           | Unit
+//! ------------|--------------------------------------|----------------|--------
+//! `min`       | Minimum temperature among all inputs | Number         | Degrees
+//! `average`   | Average temperature among all inputs | Number         | Degrees
+//! `max`       | Maximum temperature among all inputs | Number         | Degrees
+//! `temps`     | Temperatures of all inputs           | List of Number | Degrees
 //!
 //! Note that when block is collapsed, no placeholders are provided.
 //!
+//! # Actions
+//!
+//! Action | Description | Default button
+//! -------|-------------|---------------
+//! `toggle_collapsed` | Collapse or expand the block | Left
+//!
 //! # Example
 //!
 //! ```toml
 //! [[block]]
 //! block = "temperature"
-//! format = "{min} min, {max} max, {average} avg|"
+//! format = "$min min, $max max, $average avg|"
 //! interval = 10
 //! chip = "*-isa-*"
 //! ```
 //!
+//! Temperatures are reported in degrees Celsius. Use the unit argument of the formatter to
+//! display them in Fahrenheit (this replaces the `scale` option):
+//!
+//! ```toml
+//! [[block]]
+//! block = "temperature"
+//! format = "$average.eng(3,degF) avg"
+//! ```
+//!
 //! # Icons Used
 //! - `thermometer`
 
 use std::collections::HashMap;
 
 use super::prelude::*;
+use crate::formatting::unit::Unit;
 
 use sensors::FeatureType::SENSORS_FEATURE_TEMP;
 use sensors::Sensors;
@@ -90,35 +107,56 @@ enum TemperatureScale {
 }
 
 impl TemperatureScale {
-    #[allow(clippy::wrong_self_convention)]
-    pub fn from_celsius(self, val: f64) -> f64 {
+    fn unit(self) -> Unit {
         match self {
-            Self::Celsius => val,
-            Self::Fahrenheit => val * 1.8 + 32.0,
+            Self::Celsius => Unit::Degrees,
+            Self::Fahrenheit => Unit::Fahrenheit,
         }
     }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn from_celsius(self, val: f64) -> f64 {
+        Unit::Degrees.convert(val, self.unit()).unwrap()
+    }
 }
 
+const DEFAULT_FORMAT: &str = "$average avg, $max max";
+
+const ACTIONS: &[BlockAction] = &[("toggle_collapsed", &[MouseButton::Left])];
+
+const ICON: &str = "thermometer";
+
+check_config!(
+    config: TemperatureConfig,
+    formats { format => DEFAULT_FORMAT },
+    icons [ICON],
+    actions ACTIONS,
+);
+
 pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     let mut events = api.get_events().await?;
     let config = TemperatureConfig::deserialize(config).config_error()?;
+    api.set_actions(ACTIONS);
     let mut collapsed = config.collapsed;
-    api.set_format(config.format.with_default("$average avg, $max max")?);
-    api.set_icon("thermometer")?;
+    api.set_format(config.format.with_default(DEFAULT_FORMAT)?);
+    api.set_icon(ICON)?;
 
+    // Thresholds are given in the (deprecated) `scale`
+    let scale = config.scale;
     let good = config
         .good
-        .unwrap_or_else(|| config.scale.from_celsius(DEFAULT_GOOD));
+        .unwrap_or_else(|| scale.from_celsius(DEFAULT_GOOD));
     let idle = config
         .idle
-        .unwrap_or_else(|| config.scale.from_celsius(DEFAULT_IDLE));
+        .unwrap_or_else(|| scale.from_celsius(DEFAULT_IDLE));
     let info = config
         .info
-        .unwrap_or_else(|| config.scale.from_celsius(DEFAULT_INFO));
+        .unwrap_or_else(|| scale.from_celsius(DEFAULT_INFO));
     let warn = config
         .warning
-        .unwrap_or_else(|| config.scale.from_celsius(DEFAULT_WARN));
+        .unwrap_or_else(|| scale.from_celsius(DEFAULT_WARN));
 
+    let mut timer = config.interval.timer();
     loop {
         // Perhaps it's better to just Box::leak() once and don't clone() every time?
         let chip = config.chip.clone();
@@ -147,7 +185,7 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
                         if *subfeat.subfeature_type() == SENSORS_SUBFEATURE_TEMP_INPUT {
                             if let Ok(value) = subfeat.get_value() {
                                 if (-100.0..=150.0).contains(&value) {
-                                    vals.push(config.scale.from_celsius(value));
+                                    vals.push(scale.from_celsius(value));
                                 } else {
                                     eprintln!(
                                         "Temperature ({}) outside of range ([-100, 150])",
@@ -188,10 +226,12 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
             if collapsed {
                 api.set_values(HashMap::new());
             } else {
+                let temp_value = |t: f64| Value::number_unit(t, scale.unit());
                 api.set_values(map! {
-                    "average" => Value::degrees(avg_temp),
-                    "min" => Value::degrees(min_temp),
-                    "max" => Value::degrees(max_temp),
+                    "average" => temp_value(avg_temp),
+                    "min" => temp_value(min_temp),
+                    "max" => temp_value(max_temp),
+                    "temps" => Value::list(temp.iter().map(|t| temp_value(*t)).collect()),
                 });
             }
 
@@ -199,9 +239,10 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
 
             loop {
                 tokio::select! {
-                    _ = sleep(config.interval.0) => break 'outer,
-                    Some(BlockEvent::Click(click)) = events.recv() => {
-                        if click.button == MouseButton::Left  {
+                    _ = timer.tick() => break 'outer,
+                    _ = api.wait_for_update_request() => break 'outer,
+                    Some(event) = events.recv() => {
+                        if let BlockEvent::Action("toggle_collapsed") = event {
                             collapsed = !collapsed;
                             break;
                         }
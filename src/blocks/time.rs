@@ -44,17 +44,23 @@ struct TimeConfig {
     locale: Option<String>,
 }
 
+const DEFAULT_FORMAT: &str = "%a %d/%m %R";
+
+const ICON: &str = "time";
+
+check_config!(config: TimeConfig, formats { format => DEFAULT_FORMAT }, icons [ICON]);
+
 pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     let config = TimeConfig::deserialize(config).config_error()?;
-    api.set_icon("time")?;
+    api.set_icon(ICON)?;
 
     // `FormatTemplate` doesn't do much stuff here - we just want to get the original "full" and
     // "short" formats, so we "render" it without providing any placeholders.
     let (format, format_short) = config
         .format
-        .with_default("%a %d/%m %R")?
+        .with_default(DEFAULT_FORMAT)?
         .run_no_init()
-        .render(&HashMap::new())?;
+        .render(&HashMap::new(), &api.shared_config)?;
     let format = format.as_str();
     let format_short = format_short.as_deref();
 
@@ -77,7 +83,10 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
         }
         api.flush().await?;
 
-        timer.tick().await;
+        tokio::select! {
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
+        }
     }
 }
 
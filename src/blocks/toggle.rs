@@ -22,6 +22,12 @@
 //! `icon_off` | Icon override for the toggle button while off | No | `"toggle_off"`
 //! `interval` | Update interval in seconds. If not set, `command_state` will run only on click. | No | None
 //!
+//! # Actions
+//!
+//! Action | Description | Default button
+//! -------|-------------|---------------
+//! `toggle` | Run `command_on` or `command_off` | Left
+//!
 //! # Examples
 //!
 //! This is what can be used to toggle an external monitor configuration:
@@ -60,17 +66,34 @@ pub struct ToggleConfig {
     interval: Option<u64>,
 }
 
+const DEFAULT_ICON_ON: &str = "toggle_on";
+const DEFAULT_ICON_OFF: &str = "toggle_off";
+
+const ACTIONS: &[BlockAction] = &[("toggle", &[MouseButton::Left])];
+
+check_config!(
+    config: ToggleConfig,
+    icons [
+        config.icon_on.as_deref().unwrap_or(DEFAULT_ICON_ON),
+        config.icon_off.as_deref().unwrap_or(DEFAULT_ICON_OFF),
+    ],
+    actions ACTIONS,
+);
+
 pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     let mut events = api.get_events().await?;
     let config = ToggleConfig::deserialize(config).config_error()?;
-    let interval = config.interval.map(Duration::from_secs);
+    let mut timer = config
+        .interval
+        .map(|interval| Seconds::new(interval).timer());
+    api.set_actions(ACTIONS);
 
     if let Some(text) = config.text {
         api.set_text(text);
     }
 
-    let icon_on = config.icon_on.unwrap_or_else(|| "toggle_on".into());
-    let icon_off = config.icon_off.unwrap_or_else(|| "toggle_off".into());
+    let icon_on = config.icon_on.unwrap_or_else(|| DEFAULT_ICON_ON.into());
+    let icon_off = config.icon_off.unwrap_or_else(|| DEFAULT_ICON_OFF.into());
 
     // Choose the shell in this priority:
     // 1) `SHELL` environment varialble
@@ -93,55 +116,34 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
         api.set_icon(if is_toggled { &icon_on } else { &icon_off })?;
         api.flush().await?;
 
-        // TODO: try not to duplicate code
         loop {
-            match interval {
-                Some(interval) => {
-                    tokio::select! {
-                        _ = sleep(interval) => break,
-                        Some(BlockEvent::Click(click)) = events.recv() => {
-                            if click.button == MouseButton::Left {
-                                let cmd = if is_toggled {
-                                    &config.command_off
-                                } else {
-                                    &config.command_on
-                                };
-                                let output = Command::new(&shell)
-                                    .args(&["-c", cmd])
-                                    .output()
-                                    .await
-                                    .error("Failed to run command")?;
-                                if output.status.success() {
-                                    api.set_state(State::Idle);
-                                    break;
-                                } else {
-                                    api.set_state(State::Critical);
-                                }
-                            }
-                        },
-                    }
-                }
-                None => {
-                    if let Some(BlockEvent::Click(click)) = events.recv().await {
-                        if click.button == MouseButton::Left {
-                            let cmd = if is_toggled {
-                                &config.command_off
-                            } else {
-                                &config.command_on
-                            };
-                            let output = Command::new(&shell)
-                                .args(&["-c", cmd])
-                                .output()
-                                .await
-                                .error("Failed to run command")?;
-                            if output.status.success() {
-                                api.set_state(State::Idle);
-                                break;
-                            } else {
-                                api.set_state(State::Critical);
-                            }
-                        }
-                    }
+            let event = match &mut timer {
+                Some(timer) => tokio::select! {
+                    _ = timer.tick() => break,
+                    _ = api.wait_for_update_request() => break,
+                    Some(event) = events.recv() => Some(event),
+                },
+                None => tokio::select! {
+                    _ = api.wait_for_update_request() => break,
+                    event = events.recv() => event,
+                },
+            };
+            if let Some(BlockEvent::Action("toggle")) = event {
+                let cmd = if is_toggled {
+                    &config.command_off
+                } else {
+                    &config.command_on
+                };
+                let output = Command::new(&shell)
+                    .args(&["-c", cmd])
+                    .output()
+                    .await
+                    .error("Failed to run command")?;
+                if output.status.success() {
+                    api.set_state(State::Idle);
+                    break;
+                } else {
+                    api.set_state(State::Critical);
                 }
             }
         }
@@ -9,6 +9,22 @@
 //! `show_time` | Whether to show recorded time. | No | `false`
 //! `state_path` | Path to the Watson state file. | No | `$XDG_CONFIG_HOME/watson/state`
 //! `interval` | Update interval, in seconds. | No | `60`
+//! `format` | A string to customise the output of this block. See below for available placeholders. | No | <code>"$project{ [$tags]&vert;}{$stopped{ stopped $time}&vert;{ started $time}&vert;}"</code>
+//!
+//! Placeholder | Value                                                                   | Type      | Unit
+//! ------------|-------------------------------------------------------------------------|-----------|-----
+//! `project`   | The name of the current (or just stopped) project                       | Text      | -
+//! `tags`      | Space separated tags of the project. Absent if there are no tags.       | Text      | -
+//! `start`     | When the time tracking was started                                      | Timestamp | -
+//! `elapsed`   | Time since the start. Present only if `show_time` is enabled.           | Duration  | -
+//! `time`      | `elapsed` in words, e.g. `2 hours ago` or `after 2 hours` once stopped  | Text      | -
+//! `stopped`   | Present only if the time tracking has just been stopped                 | Flag      | -
+//!
+//! # Actions
+//!
+//! Action | Description | Default button
+//! -------|-------------|---------------
+//! `toggle_show_time` | Show or hide the elapsed time | Left
 //!
 //! # Example
 //!
@@ -17,11 +33,13 @@
 //! block = "watson"
 //! show_time = true
 //! state_path = "/home/user/.config/watson/state"
+//! format = "$project {$elapsed.dur(hm)|since $start.datetime(%R)}"
 //! ```
 //!
 //! # TODO
 //! - Extend functionality: start / stop watson using this block
 
+use std::collections::HashMap;
 use std::path::PathBuf;
 use tokio::fs::read_to_string;
 
@@ -40,6 +58,7 @@ struct WatsonConfig {
     state_path: Option<ShellString>,
     interval: Seconds,
     show_time: bool,
+    format: FormatConfig,
 }
 
 impl Default for WatsonConfig {
@@ -48,13 +67,22 @@ impl Default for WatsonConfig {
             state_path: None,
             interval: Seconds::new(60),
             show_time: false,
+            format: FormatConfig::default(),
         }
     }
 }
 
+const DEFAULT_FORMAT: &str = "$project{ [$tags]|}{$stopped{ stopped $time}|{ started $time}|}";
+
+const ACTIONS: &[BlockAction] = &[("toggle_show_time", &[MouseButton::Left])];
+
+check_config!(config: WatsonConfig, formats { format => DEFAULT_FORMAT }, actions ACTIONS);
+
 pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     let config = WatsonConfig::deserialize(config).config_error()?;
+    api.set_actions(ACTIONS);
     let mut events = api.get_events().await?;
+    api.set_format(config.format.with_default(DEFAULT_FORMAT)?);
 
     let mut show_time = config.show_time;
 
@@ -85,7 +113,7 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
         .error("Failed to create event stream")?;
 
     let mut timer = config.interval.timer();
-    let mut prev_state = None;
+    let mut prev_frame = None;
 
     loop {
         let state = read_to_string(&state_path)
@@ -93,28 +121,27 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
             .error("Failed to read state file")?;
         let state = serde_json::from_str(&state).error("Fnable to deserialize state")?;
         match state {
-            state @ WatsonState::Active { .. } => {
+            WatsonState::Active(frame) => {
+                api.show();
                 api.set_state(State::Good);
-                api.set_text(state.format(show_time, "started", format_delta_past));
-                prev_state = Some(state);
+                api.set_values(frame.values(show_time, false));
+                prev_frame = Some(frame);
             }
             WatsonState::Idle {} => {
-                if let Some(prev @ WatsonState::Active { .. }) = &prev_state {
+                if let Some(prev) = prev_frame.take() {
                     // The previous state was active, which means that we just now stopped the time
                     // tracking. This means that we could show some statistics.
                     show_time = true;
-                    api.set_text(prev.format(true, "stopped", format_delta_after));
+                    api.show();
+                    api.set_values(prev.values(true, true));
                     api.set_state(State::Idle {});
-                    prev_state = Some(state);
                 } else {
                     // File is empty which means that there is currently no active time tracking,
                     // and the previous state wasn't time tracking neither so we reset the
                     // contents.
                     show_time = false;
                     api.set_state(State::Idle {});
-                    api.set_text(String::new());
-
-                    prev_state = Some(state);
+                    api.hide();
                 }
             }
         }
@@ -124,14 +151,15 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
         loop {
             tokio::select! {
                 _ = timer.tick() => break,
+                _ = api.wait_for_update_request() => break,
                 Some(update) = state_updates.next() => {
                     let update = update.error("Bad inoify update")?;
                     if update.name.map(|x| state_file == x).unwrap_or(false) {
                         break;
                     }
                 }
-                Some(BlockEvent::Click(event)) = events.recv() => {
-                    if event.button == MouseButton::Left {
+                Some(event) = events.recv() => {
+                    if let BlockEvent::Action("toggle_show_time") = event {
                         show_time = !show_time;
                         break;
                     }
@@ -176,44 +204,44 @@ fn format_delta_after(delta: &chrono::Duration) -> String {
 #[derive(Deserialize, Clone, Debug)]
 #[serde(untagged)]
 enum WatsonState {
-    Active {
-        project: String,
-        #[serde(deserialize_with = "deserialize_local_timestamp")]
-        start: DateTime<Local>,
-        tags: Vec<String>,
-    },
+    Active(Frame),
     // This matches an empty JSON object
     Idle {},
 }
 
-impl WatsonState {
-    fn format(&self, show_time: bool, verb: &str, f: fn(&chrono::Duration) -> String) -> String {
-        if let WatsonState::Active {
-            project,
-            start,
-            tags,
-        } = self
-        {
-            let mut s = project.clone();
-            if let [first, other @ ..] = &tags[..] {
-                s.push_str(" [");
-                s.push_str(first);
-                for tag in other {
-                    s.push(' ');
-                    s.push_str(tag);
-                }
-                s.push(']');
-            }
-            if show_time {
-                s.push(' ');
-                s.push_str(verb);
-                let delta = Local::now() - *start;
-                s.push(' ');
-                s.push_str(&f(&delta));
+/// The project which is currently tracked
+#[derive(Deserialize, Clone, Debug)]
+struct Frame {
+    project: String,
+    #[serde(deserialize_with = "deserialize_local_timestamp")]
+    start: DateTime<Local>,
+    tags: Vec<String>,
+}
+
+impl Frame {
+    fn values(&self, show_time: bool, stopped: bool) -> HashMap<String, Value> {
+        let mut values = map! {
+            "project" => Value::text(self.project.clone()),
+            "start" => Value::timestamp(self.start),
+        };
+        if !self.tags.is_empty() {
+            values.insert("tags".into(), Value::text(self.tags.join(" ").into()));
+        }
+        if show_time {
+            let delta = Local::now() - self.start;
+            if let Ok(elapsed) = delta.to_std() {
+                values.insert("elapsed".into(), Value::duration(elapsed));
             }
-            s
-        } else {
-            panic!("WatsonState::Idle does not have a specified format")
+            let time = if stopped {
+                format_delta_after(&delta)
+            } else {
+                format_delta_past(&delta)
+            };
+            values.insert("time".into(), Value::text(time));
+        }
+        if stopped {
+            values.insert("stopped".into(), Value::Flag);
         }
+        values
     }
 }
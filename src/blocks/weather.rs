@@ -29,7 +29,7 @@
 //! `city_id` | OpenWeatherMap's ID for the city. | Yes* | None
 //! `place` | OpenWeatherMap 'By city name' search query. See [here](https://openweathermap.org/current) | Yes* | None
 //! `coordinates` | GPS latitude longitude coordinates as a tuple, example: `["39.236229089090216","9.331730718685696"]`
-//! `units` | Either `metric` or `imperial`. | Yes | `metric`
+//! `units` | Deprecated, use the unit argument of the formatter instead. Either `metric` or `imperial`. With `imperial`, temperatures are reported in °F and wind speeds in mph. | No | `metric`
 //! `lang` | Language code. See [here](https://openweathermap.org/current#multi). Currently only affects `weather_verbose` key. | No | `en`
 //!
 //! One of `city_id`, `place` or `coordinates` is required. If more than one are supplied, `city_id` takes precedence over `place` which takes place over `coordinates`.
@@ -38,6 +38,9 @@
 //! in which case they must be provided in the environment variables
 //! `OPENWEATHERMAP_API_KEY`, `OPENWEATHERMAP_CITY_ID`, `OPENWEATHERMAP_PLACE`.
 //!
+//! Values are reported in metric units. Use the unit argument of the formatter to convert them,
+//! e.g. `$temp.eng(2,degF)` or `$wind_speed.eng(2,mph)`.
+//!
 //! # Available Format Keys
 //!
 //!  Key              | Value                                                              | Type   | Unit
@@ -48,8 +51,9 @@
 //! `humidity`        | Humidity                                                           | Number | %
 //! `weather`         | Textual brief description of the weather, e.g. "Raining"           | Text   | -
 //! `weather_verbose` | Textual verbose description of the weather, e.g. "overcast clouds" | Text   | -
-//! `wind`            | Wind speed                                                         | Number | -
-//! `wind_kmh`        | Wind speed. The wind speed in km/h                                 | Number | -
+//! `wind_speed`      | Wind speed                                                         | Number | m/s
+//! `wind`            | Deprecated, `$wind_speed` without the unit                         | Number | -
+//! `wind_kmh`        | Deprecated, `$wind_speed.eng(2,km/h)` without the unit             | Number | -
 //! `direction`       | Wind direction, e.g. "NE"                                          | Text   | -
 //!
 //! # Example
@@ -59,8 +63,8 @@
 //! ```toml
 //! [[block]]
 //! block = "weather"
-//! format = "$weather ($location) $temp, $wind m/s $direction"
-//! service = { name = "openweathermap", api_key = "XXX", city_id = "5398563", units = "metric" }
+//! format = "$weather ($location) $temp, $wind_speed.eng(1,km/h) $direction"
+//! service = { name = "openweathermap", api_key = "XXX", city_id = "5398563" }
 //! ```
 //!
 //! # Used Icons
@@ -73,6 +77,7 @@
 //! - `weather_default` (in all other cases)
 
 use super::prelude::*;
+use crate::formatting::unit::Unit;
 
 const IP_API_URL: &str = "https://ipapi.co/json";
 
@@ -136,45 +141,61 @@ impl WeatherService {
     }
 }
 
+const DEFAULT_FORMAT: &str = "$weather $temp";
+
+const ICON_SUN: &str = "weather_sun";
+const ICON_RAIN: &str = "weather_rain";
+const ICON_CLOUDS: &str = "weather_clouds";
+const ICON_THUNDER: &str = "weather_thunder";
+const ICON_SNOW: &str = "weather_snow";
+const ICON_DEFAULT: &str = "weather_default";
+
+check_config!(
+    config: WeatherConfig,
+    formats { format => DEFAULT_FORMAT },
+    icons [
+        ICON_SUN,
+        ICON_RAIN,
+        ICON_CLOUDS,
+        ICON_THUNDER,
+        ICON_SNOW,
+        ICON_DEFAULT,
+    ],
+);
+
 pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     let config = WeatherConfig::deserialize(config).config_error()?;
-    api.set_format(config.format.with_default("$weather $temp")?);
+    api.set_format(config.format.with_default(DEFAULT_FORMAT)?);
 
+    let mut timer = config.interval.timer();
     loop {
         if let Ok(data) = config.service.get(config.autolocate).await {
-            let apparent_temp = australian_apparent_temp(
-                data.main.temp,
-                data.main.humidity,
-                data.wind.speed,
-                config.service.units(),
-            );
-
-            let kmh_wind_speed = data.wind.speed
-                * 3.6
-                * match config.service.units() {
-                    UnitSystem::Metric => 1.0,
-                    UnitSystem::Imperial => 0.447,
-                };
+            let apparent_temp =
+                australian_apparent_temp(data.main.temp, data.main.humidity, data.wind.speed);
 
+            let units = config.service.units();
+            let wind_speed = units.speed(data.wind.speed);
             let keys = map! {
                 "location" => Value::text(data.name),
-                "temp" => Value::degrees(data.main.temp),
-                "apparent" => Value::degrees(apparent_temp),
+                "temp" => units.temperature(data.main.temp),
+                "apparent" => units.temperature(apparent_temp),
                 "humidity" => Value::percents(data.main.humidity),
                 "weather" => Value::text(data.weather[0].main.clone()),
                 "weather_verbose" => Value::text(data.weather[0].description.clone()),
-                "wind" => Value::number(data.wind.speed),
-                "wind_kmh" => Value::number(kmh_wind_speed),
+                "wind_speed" => Value::number_unit(wind_speed, units.speed_unit()),
+                // The deprecated keys have no unit, older formats write it after them
+                "wind" => Value::number(wind_speed),
+                "wind_kmh" => Value::number(data.wind.speed * 3.6),
                 "direction" => Value::text(convert_wind_direction(data.wind.deg).into()),
             };
 
             let icon = match data.weather[0].main.as_str() {
-                "Clear" => "weather_sun",
-                "Rain" | "Drizzle" => "weather_rain",
-                "Clouds" | "Fog" | "Mist" => "weather_clouds",
-                "Thunderstorm" => "weather_thunder",
-                "Snow" => "weather_snow",
-                _ => "weather_default",
+                "Clear" => ICON_SUN,
+                "Rain" | "Drizzle" => ICON_RAIN,
+                "Clouds" | "Fog" | "Mist" => ICON_CLOUDS,
+                "Thunderstorm" => ICON_THUNDER,
+                "Snow" => ICON_SNOW,
+                _ => ICON_DEFAULT,
             };
 
             api.set_icon(icon)?;
@@ -185,7 +206,10 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
 
         api.flush().await?;
 
-        sleep(config.interval.0).await;
+        tokio::select! {
+            _ = timer.tick() => (),
+            _ = api.wait_for_update_request() => (),
+        }
     }
 }
 
@@ -227,8 +251,8 @@ impl WeatherService {
             city_id,
             place,
             coordinates,
-            units,
             lang,
+            ..
         } = self;
 
         let api_key = api_key.as_ref().or_error(|| {
@@ -258,15 +282,8 @@ impl WeatherService {
 
         // Refer to https://openweathermap.org/current
         let url = &format!(
-            "{}?{}&appid={}&units={}&lang={}",
-            OPEN_WEATHER_MAP_URL,
-            location_query,
-            api_key,
-            match *units {
-                UnitSystem::Metric => "metric",
-                UnitSystem::Imperial => "imperial",
-            },
-            lang,
+            "{}?{}&appid={}&units=metric&lang={}",
+            OPEN_WEATHER_MAP_URL, location_query, api_key, lang,
         );
 
         reqwest::get(url)
@@ -278,6 +295,7 @@ impl WeatherService {
     }
 }
 
+/// The units in which the values are reported. The data is always requested in metric units.
 #[derive(Derivative, Copy, Clone, Debug, Deserialize, PartialEq)]
 #[serde(rename_all = "lowercase")]
 #[derivative(Default)]
@@ -287,6 +305,30 @@ enum UnitSystem {
     Imperial,
 }
 
+impl UnitSystem {
+    fn temperature(self, celsius: f64) -> Value {
+        let unit = match self {
+            Self::Metric => Unit::Degrees,
+            Self::Imperial => Unit::Fahrenheit,
+        };
+        Value::number_unit(Unit::Degrees.convert(celsius, unit).unwrap(), unit)
+    }
+
+    fn speed_unit(self) -> Unit {
+        match self {
+            Self::Metric => Unit::MetersPerSecond,
+            Self::Imperial => Unit::MilesPerHour,
+        }
+    }
+
+    /// The speed in `speed_unit`
+    fn speed(self, meters_per_second: f64) -> f64 {
+        Unit::MetersPerSecond
+            .convert(meters_per_second, self.speed_unit())
+            .unwrap()
+    }
+}
+
 // TODO: might be good to allow for different geolocation services to be used, similar to how we have `service` for the weather API
 async fn find_ip_location() -> Result<Option<String>> {
     #[derive(Deserialize)]
@@ -306,33 +348,10 @@ async fn find_ip_location() -> Result<Option<String>> {
 
 // Compute the Australian Apparent Temperature (AT),
 // using the metric formula found on Wikipedia.
-// If using imperial units, we must first convert to metric.
-fn australian_apparent_temp(
-    raw_temp: f64,
-    raw_humidity: f64,
-    raw_wind_speed: f64,
-    units: UnitSystem,
-) -> f64 {
-    let temp_celsius = match units {
-        UnitSystem::Metric => raw_temp,
-        UnitSystem::Imperial => (raw_temp - 32.0) * 0.556,
-    };
-
+fn australian_apparent_temp(temp_celsius: f64, humidity: f64, wind_speed: f64) -> f64 {
     let exponent = 17.27 * temp_celsius / (237.7 + temp_celsius);
-    let water_vapor_pressure = raw_humidity * 0.06105 * exponent.exp();
-
-    let metric_wind_speed = match units {
-        UnitSystem::Metric => raw_wind_speed,
-        UnitSystem::Imperial => raw_wind_speed * 0.447,
-    };
-
-    let metric_apparent_temp =
-        temp_celsius + 0.33 * water_vapor_pressure - 0.7 * metric_wind_speed - 4.0;
-
-    match units {
-        UnitSystem::Metric => metric_apparent_temp,
-        UnitSystem::Imperial => 1.8 * metric_apparent_temp + 32.,
-    }
+    let water_vapor_pressure = humidity * 0.06105 * exponent.exp();
+    temp_celsius + 0.33 * water_vapor_pressure - 0.7 * wind_speed - 4.0
 }
 
 // Convert wind direction in azimuth degrees to abbreviation names
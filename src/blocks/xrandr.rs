@@ -20,6 +20,14 @@
 //! `resolution`      | The resolution of a monitor  | Text   | -
 //! `res_icon`        | A static icon                | Icon   | -
 //!
+//! # Actions
+//!
+//! Action | Description | Default button
+//! -------|-------------|---------------
+//! `next_monitor` | Show the next monitor | Left
+//! `brightness_up` | Increase the brightness of the shown monitor by `step_width` | Wheel up
+//! `brightness_down` | Decrease the brightness of the shown monitor by `step_width` | Wheel down
+//!
 //! # Example
 //!
 //! ```toml
@@ -59,16 +67,32 @@ impl Default for XrandrConfig {
     }
 }
 
+const DEFAULT_FORMAT: &str = "$display $brightness_icon $brightness";
+
+const ACTIONS: &[BlockAction] = &[
+    ("next_monitor", &[MouseButton::Left]),
+    ("brightness_up", &[MouseButton::WheelUp]),
+    ("brightness_down", &[MouseButton::WheelDown]),
+];
+
+const ICON: &str = "xrandr";
+const ICON_BRIGHTNESS: &str = "backlight_full";
+const ICON_RESOLUTION: &str = "resolution";
+
+check_config!(
+    config: XrandrConfig,
+    formats { format => DEFAULT_FORMAT },
+    icons [ICON, ICON_BRIGHTNESS, ICON_RESOLUTION],
+    actions ACTIONS,
+);
+
 pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
     let mut events = api.get_events().await?;
     let config = XrandrConfig::deserialize(config).config_error()?;
+    api.set_actions(ACTIONS);
 
-    api.set_format(
-        config
-            .format
-            .with_default("$display $brightness_icon $brightness")?,
-    );
-    api.set_icon("xrandr")?;
+    api.set_format(config.format.with_default(DEFAULT_FORMAT)?);
+    api.set_icon(ICON)?;
 
     let display = |monitor: Option<&Monitor>, api: &mut CommonApi| {
         if let Some(mon) = monitor {
@@ -76,9 +100,9 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
                 "display" => Value::text(mon.name.clone()),
                 "brightness" => Value::percents(mon.brightness),
                 //TODO: change `brightness_icon` based on `brightness`
-                "brightness_icon" => Value::Icon(api.get_icon("backlight_full")?),
+                "brightness_icon" => Value::Icon(api.get_icon(ICON_BRIGHTNESS)?),
                 "resolution" => Value::text(mon.resolution.clone()),
-                "res_icon" => Value::Icon(api.get_icon("resolution")?),
+                "res_icon" => Value::Icon(api.get_icon(ICON_RESOLUTION)?),
             });
         } else {
             api.set_values(HashMap::new());
@@ -101,21 +125,22 @@ pub async fn run(config: toml::Value, mut api: CommonApi) -> Result<()> {
         loop {
             tokio::select! {
                 _ = timer.tick() => break,
-                Some(BlockEvent::Click(click)) = events.recv() => {
-                    match click.button {
-                        MouseButton::Left => {
+                _ = api.wait_for_update_request() => break,
+                Some(event) = events.recv() => {
+                    match event {
+                        BlockEvent::Action("next_monitor") => {
                             cur_indx += 1;
                             if cur_indx >= monitors.len() {
                                 cur_indx = 0;
                             }
                         }
-                        MouseButton::WheelUp => {
+                        BlockEvent::Action("brightness_up") => {
                             if let Some(monitor) = monitors.get_mut(cur_indx) {
                                 let bright = (monitor.brightness + config.step_width).min(100);
                                 monitor.set_brightness(bright);
                             }
                         }
-                        MouseButton::WheelDown => {
+                        BlockEvent::Action("brightness_down") => {
                             if let Some(monitor) = monitors.get_mut(cur_indx) {
                                 let bright = monitor.brightness.saturating_sub(config.step_width);
                                 monitor.set_brightness(bright);
@@ -1,9 +1,14 @@
 use std::fmt;
+use std::ops::BitOr;
+use std::str::FromStr;
 
 use serde::de::{self, Deserialize, Deserializer, Visitor};
 
-use crate::errors::{self, ResultExt};
-use crate::subprocess::{spawn_shell, spawn_shell_sync};
+use crate::blocks::BlockAction;
+use crate::errors::{self, OptionExt, ResultExt, ToSerdeError};
+use crate::formatting::Values;
+use crate::protocol::i3bar_event::I3BarEvent;
+use crate::subprocess::{spawn_shell_sync_with_env, spawn_shell_with_env};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum MouseButton {
@@ -15,26 +20,216 @@ pub enum MouseButton {
     Forward,
     Back,
     Unknown,
-    /// Experemental
-    DoubleLeft,
+}
+
+impl Default for MouseButton {
+    fn default() -> Self {
+        Self::Unknown
+    }
+}
+
+impl MouseButton {
+    pub fn from_name(name: &str) -> Self {
+        use MouseButton::*;
+        match name {
+            "left" => Left,
+            "middle" => Middle,
+            "right" => Right,
+            "up" => WheelUp,
+            "down" => WheelDown,
+            "forward" => Forward,
+            "back" => Back,
+            _ => Unknown,
+        }
+    }
+
+    pub fn name(self) -> &'static str {
+        use MouseButton::*;
+        match self {
+            Left => "left",
+            Middle => "middle",
+            Right => "right",
+            WheelUp => "up",
+            WheelDown => "down",
+            Forward => "forward",
+            Back => "back",
+            Unknown => "unknown",
+        }
+    }
+
+    pub fn from_number(number: i64) -> Self {
+        use MouseButton::*;
+        match number {
+            1 => Left,
+            2 => Middle,
+            3 => Right,
+            4 => WheelUp,
+            5 => WheelDown,
+            9 => Forward,
+            8 => Back,
+            _ => Unknown,
+        }
+    }
+}
+
+/// The keyboard modifiers held during a click
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct Modifiers(u8);
+
+impl Modifiers {
+    pub const SHIFT: Self = Self(1 << 0);
+    pub const CONTROL: Self = Self(1 << 1);
+    pub const MOD1: Self = Self(1 << 2);
+    pub const MOD2: Self = Self(1 << 3);
+    pub const MOD3: Self = Self(1 << 4);
+    pub const MOD4: Self = Self(1 << 5);
+    pub const MOD5: Self = Self(1 << 6);
+    pub const LOCK: Self = Self(1 << 7);
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Some(match name.to_ascii_lowercase().as_str() {
+            "shift" => Self::SHIFT,
+            "control" | "ctrl" => Self::CONTROL,
+            "mod1" | "alt" => Self::MOD1,
+            "mod2" => Self::MOD2,
+            "mod3" => Self::MOD3,
+            "mod4" | "super" => Self::MOD4,
+            "mod5" => Self::MOD5,
+            "lock" => Self::LOCK,
+            _ => return None,
+        })
+    }
+
+    /// Caps Lock and Num Lock (usually `Mod2`) should not change the meaning of a click
+    fn without_locks(self) -> Self {
+        Self(self.0 & !(Self::LOCK.0 | Self::MOD2.0))
+    }
+}
+
+impl BitOr for Modifiers {
+    type Output = Self;
+    fn bitor(self, rhs: Self) -> Self {
+        Self(self.0 | rhs.0)
+    }
+}
+
+/// A mouse button with the number of clicks and the modifiers that must be held, e.g.
+/// `"Shift+double_left"`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ClickButton {
+    pub button: MouseButton,
+    pub clicks: u8,
+    pub modifiers: Modifiers,
+}
+
+impl FromStr for ClickButton {
+    type Err = errors::Error;
+
+    fn from_str(s: &str) -> errors::Result<Self> {
+        let mut parts = s.rsplit('+');
+        let name = parts.next().unwrap_or_default();
+        let (clicks, name) = if let Some(name) = name.strip_prefix("double_") {
+            (2, name)
+        } else if let Some(name) = name.strip_prefix("triple_") {
+            (3, name)
+        } else {
+            (1, name)
+        };
+        let button = MouseButton::from_name(name);
+        let mut modifiers = Modifiers::default();
+        for modifier in parts {
+            modifiers = modifiers
+                | Modifiers::from_name(modifier)
+                    .or_error(|| format!("Unknown modifier '{}'", modifier))?;
+        }
+        Ok(Self {
+            button,
+            clicks,
+            modifiers,
+        })
+    }
 }
 
 #[derive(serde_derive::Deserialize, Debug, Clone, Default)]
 pub struct ClickHandler(Vec<ClickConfigEntry>);
 
 impl ClickHandler {
+    /// Find the entry for the click. Entries without modifiers are used if there is no entry for
+    /// the exact combination.
+    fn find(&self, event: &I3BarEvent) -> Option<&ClickConfigEntry> {
+        let modifiers = event.modifiers.without_locks();
+        let is_match = |e: &&ClickConfigEntry, modifiers: Modifiers| {
+            e.button.button == event.button
+                && e.button.clicks == event.clicks
+                && e.button.modifiers == modifiers
+        };
+        self.0
+            .iter()
+            .find(|e| is_match(e, modifiers))
+            .or_else(|| self.0.iter().find(|e| is_match(e, Modifiers::default())))
+    }
+
+    /// The maximum number of consecutive clicks of `button` that have an entry
+    pub fn max_clicks(&self, button: MouseButton) -> u8 {
+        self.0
+            .iter()
+            .filter(|e| e.button.button == button)
+            .map(|e| e.button.clicks)
+            .max()
+            .unwrap_or(1)
+    }
+
+    /// The action bound to the click, if any
+    pub fn action(&self, event: &I3BarEvent) -> Option<&str> {
+        self.find(event).and_then(|e| e.action.as_deref())
+    }
+
+    /// Check that every action bound in the config is either `refresh` or one of `actions`. All
+    /// unknown actions are reported in one error.
+    pub fn check_actions(&self, actions: &[BlockAction]) -> errors::Result<()> {
+        let unknown: Vec<_> = self
+            .0
+            .iter()
+            .filter_map(|e| e.action.as_deref())
+            .filter(|&action| {
+                action != "refresh" && !actions.iter().any(|(name, _)| *name == action)
+            })
+            .map(|action| format!("'{}'", action))
+            .collect();
+        match unknown.as_slice() {
+            [] => Ok(()),
+            [action] => Err(errors::Error::new(format!("Unknown action {}", action))),
+            _ => Err(errors::Error::new(format!(
+                "Unknown actions {}",
+                unknown.join(", ")
+            ))),
+        }
+    }
+
     // Returns true if the block needs to be updated
-    pub async fn handle(&self, button: MouseButton) -> errors::Result<bool> {
-        Ok(match self.0.iter().find(|e| e.button == button) {
+    pub async fn handle(
+        &self,
+        event: &I3BarEvent,
+        values: Option<&Values>,
+    ) -> errors::Result<bool> {
+        Ok(match self.find(event) {
             Some(entry) => {
                 if let Some(cmd) = &entry.cmd {
+                    let env = click_env(event, values);
+                    let cmd = match values {
+                        Some(values) => substitute_placeholders(cmd, values),
+                        None => cmd.clone(),
+                    };
                     if entry.sync {
-                        spawn_shell_sync(cmd).await
+                        spawn_shell_sync_with_env(&cmd, &env).await
                     } else {
-                        spawn_shell(cmd)
+                        spawn_shell_with_env(&cmd, &env)
                     }
                     .or_error(|| {
-                        format!("'{:?}' button handler: Failed to run '{}", button, cmd)
+                        format!(
+                            "'{:?}' button handler: Failed to run '{}",
+                            event.button, cmd
+                        )
                     })?;
                 }
                 entry.update
@@ -44,14 +239,97 @@ impl ClickHandler {
     }
 }
 
+/// Environment variables describing the click and the current values of the block, e.g.
+/// `SWAYSTATUS_VOLUME=45`
+fn click_env(event: &I3BarEvent, values: Option<&Values>) -> Vec<(String, String)> {
+    let mut env = vec![
+        ("SWAYSTATUS_BUTTON".into(), event.button.name().into()),
+        ("SWAYSTATUS_CLICKS".into(), event.clicks.to_string()),
+    ];
+    if let Some(instance) = event.instance {
+        env.push(("SWAYSTATUS_INSTANCE".into(), instance.to_string()));
+    }
+    for (name, value) in values.into_iter().flatten() {
+        env.push((env_var_name(name), value.to_raw_string().into()));
+    }
+    env
+}
+
+fn env_var_name(placeholder: &str) -> String {
+    let name = placeholder.chars().map(|c| match c {
+        'a'..='z' | 'A'..='Z' | '0'..='9' => c.to_ascii_uppercase(),
+        _ => '_',
+    });
+    "SWAYSTATUS_".chars().chain(name).collect()
+}
+
+/// Replace `$placeholder` with a quoted reference to the placeholder's environment variable. The
+/// value is expanded by the shell, so it can't inject commands, and it is quoted so that it isn't
+/// split into words or globbed. Placeholders are substituted inside double quotes too, but not
+/// inside single quotes, which keep their text literal like in the shell. `${name}` and escaped
+/// `\$name` are left alone, so shell variables that share a name with a placeholder are still
+/// reachable.
+fn substitute_placeholders(cmd: &str, values: &Values) -> String {
+    #[derive(PartialEq)]
+    enum Quote {
+        None,
+        Single,
+        Double,
+    }
+
+    let mut result = String::with_capacity(cmd.len());
+    let mut quote = Quote::None;
+    let mut chars = cmd.char_indices();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' if quote != Quote::Single => {
+                result.push(c);
+                if let Some((_, escaped)) = chars.next() {
+                    result.push(escaped);
+                }
+                continue;
+            }
+            '\'' if quote == Quote::None => quote = Quote::Single,
+            '\'' if quote == Quote::Single => quote = Quote::None,
+            '"' if quote == Quote::None => quote = Quote::Double,
+            '"' if quote == Quote::Double => quote = Quote::None,
+            '$' if quote != Quote::Single => {
+                let rest = &cmd[i + 1..];
+                let len = rest
+                    .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+                    .unwrap_or(rest.len());
+                let name = &rest[..len];
+                if values.contains_key(name) {
+                    let var = env_var_name(name);
+                    result.push_str(&match quote {
+                        Quote::None => format!("\"${{{}}}\"", var),
+                        _ => format!("${{{}}}", var),
+                    });
+                    // Placeholder names are ASCII
+                    for _ in 0..len {
+                        chars.next();
+                    }
+                    continue;
+                }
+            }
+            _ => (),
+        }
+        result.push(c);
+    }
+    result
+}
+
 #[derive(serde_derive::Deserialize, Debug, Clone)]
 #[serde(deny_unknown_fields)]
 pub struct ClickConfigEntry {
-    /// Which button to handle
-    button: MouseButton,
+    /// Which button to handle, optionally with modifiers (e.g. `"Shift+left"`)
+    button: ClickButton,
     /// Which command to run
     #[serde(default)]
     cmd: Option<String>,
+    /// Which action of the block to trigger
+    #[serde(default)]
+    action: Option<String>,
     /// Whether to wait for command to exit or not (default is `false`)
     #[serde(default)]
     sync: bool,
@@ -85,19 +363,7 @@ impl<'de> Deserialize<'de> for MouseButton {
             where
                 E: de::Error,
             {
-                use MouseButton::*;
-                Ok(match name {
-                    "left" => Left,
-                    "middle" => Middle,
-                    "right" => Right,
-                    "up" => WheelUp,
-                    "down" => WheelDown,
-                    "forward" => Forward,
-                    "back" => Back,
-                    // Experemental
-                    "double_left" => DoubleLeft,
-                    _ => Unknown,
-                })
+                Ok(MouseButton::from_name(name))
             }
 
             // ```toml
@@ -107,17 +373,7 @@ impl<'de> Deserialize<'de> for MouseButton {
             where
                 E: de::Error,
             {
-                use MouseButton::*;
-                Ok(match number {
-                    1 => Left,
-                    2 => Middle,
-                    3 => Right,
-                    4 => WheelUp,
-                    5 => WheelDown,
-                    9 => Forward,
-                    8 => Back,
-                    _ => Unknown,
-                })
+                Ok(MouseButton::from_number(number))
             }
             fn visit_u64<E>(self, number: u64) -> Result<MouseButton, E>
             where
@@ -130,3 +386,191 @@ impl<'de> Deserialize<'de> for MouseButton {
         deserializer.deserialize_any(MouseButtonVisitor)
     }
 }
+
+impl<'de> Deserialize<'de> for ClickButton {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct ClickButtonVisitor;
+
+        impl<'de> Visitor<'de> for ClickButtonVisitor {
+            type Value = ClickButton;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("u64 or string")
+            }
+
+            // ```toml
+            // button = "Shift+Control+left"
+            // ```
+            fn visit_str<E>(self, name: &str) -> Result<ClickButton, E>
+            where
+                E: de::Error,
+            {
+                name.parse().serde_error()
+            }
+
+            // ```toml
+            // button = 1
+            // ```
+            fn visit_i64<E>(self, number: i64) -> Result<ClickButton, E>
+            where
+                E: de::Error,
+            {
+                Ok(ClickButton {
+                    button: MouseButton::from_number(number),
+                    clicks: 1,
+                    modifiers: Modifiers::default(),
+                })
+            }
+            fn visit_u64<E>(self, number: u64) -> Result<ClickButton, E>
+            where
+                E: de::Error,
+            {
+                self.visit_i64(number as i64)
+            }
+        }
+
+        deserializer.deserialize_any(ClickButtonVisitor)
+    }
+}
+
+// i3bar sends the modifiers as a list of names
+impl<'de> Deserialize<'de> for Modifiers {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let names: Vec<String> = Deserialize::deserialize(deserializer)?;
+        Ok(names
+            .iter()
+            .filter_map(|name| Modifiers::from_name(name))
+            .fold(Modifiers::default(), BitOr::bitor))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formatting::value::Value;
+
+    fn values() -> Values {
+        map! {
+            "ip" => Value::text("1.2.3.4 5".into()),
+            "name" => Value::text("eth0".into()),
+        }
+    }
+
+    #[test]
+    fn test_click_button_from_str() {
+        let button = |s: &str| s.parse::<ClickButton>().unwrap();
+        assert_eq!(
+            button("left"),
+            ClickButton {
+                button: MouseButton::Left,
+                clicks: 1,
+                modifiers: Modifiers::default(),
+            }
+        );
+        assert_eq!(button("double_up").button, MouseButton::WheelUp);
+        assert_eq!(button("double_up").clicks, 2);
+        assert_eq!(button("triple_right").clicks, 3);
+        assert_eq!(
+            button("Shift+ctrl+double_left"),
+            ClickButton {
+                button: MouseButton::Left,
+                clicks: 2,
+                modifiers: Modifiers::SHIFT | Modifiers::CONTROL,
+            }
+        );
+        assert_eq!(button("Super+back").modifiers, Modifiers::MOD4);
+        assert_eq!(button("quadruple_left").button, MouseButton::Unknown);
+        assert!("Hyper+left".parse::<ClickButton>().is_err());
+    }
+
+    #[test]
+    fn test_max_clicks() {
+        #[derive(serde_derive::Deserialize)]
+        struct Block {
+            click: ClickHandler,
+        }
+        let handler = toml::from_str::<Block>(
+            r#"
+            click = [
+                { button = "left", cmd = "a" },
+                { button = "triple_left", cmd = "b" },
+                { button = "Shift+double_right", cmd = "c" },
+            ]
+            "#,
+        )
+        .unwrap()
+        .click;
+        assert_eq!(handler.max_clicks(MouseButton::Left), 3);
+        assert_eq!(handler.max_clicks(MouseButton::Right), 2);
+        assert_eq!(handler.max_clicks(MouseButton::Middle), 1);
+    }
+
+    #[test]
+    fn test_check_actions() {
+        #[derive(serde_derive::Deserialize)]
+        struct Block {
+            click: ClickHandler,
+        }
+        let handler = toml::from_str::<Block>(
+            r#"
+            click = [
+                { button = "left", action = "toggle" },
+                { button = "right", action = "refresh" },
+                { button = "up", action = "louder" },
+                { button = "down", action = "quieter" },
+            ]
+            "#,
+        )
+        .unwrap()
+        .click;
+        let error = handler.check_actions(&[("toggle", &[])]).unwrap_err();
+        assert_eq!(
+            error.message.as_deref(),
+            Some("Unknown actions 'louder', 'quieter'")
+        );
+        let actions: &[BlockAction] = &[("toggle", &[]), ("louder", &[]), ("quieter", &[])];
+        assert!(handler.check_actions(actions).is_ok());
+    }
+
+    #[test]
+    fn test_env_var_name() {
+        assert_eq!(env_var_name("ip"), "SWAYSTATUS_IP");
+        assert_eq!(env_var_name("cpu_freq0"), "SWAYSTATUS_CPU_FREQ0");
+        assert_eq!(env_var_name("wind-kmh"), "SWAYSTATUS_WIND_KMH");
+    }
+
+    #[test]
+    fn test_substitute_placeholders() {
+        let values = values();
+        let cases = [
+            ("echo $ip", r#"echo "${SWAYSTATUS_IP}""#),
+            (
+                "echo $ip/$name.",
+                r#"echo "${SWAYSTATUS_IP}"/"${SWAYSTATUS_NAME}"."#,
+            ),
+            (r#"echo "ip: $ip""#, r#"echo "ip: ${SWAYSTATUS_IP}""#),
+            ("echo 'ip: $ip'", "echo 'ip: $ip'"),
+            (
+                r#"echo '$ip' "$ip" '"$ip"'"#,
+                r#"echo '$ip' "${SWAYSTATUS_IP}" '"$ip"'"#,
+            ),
+            (r#"echo "it's $ip""#, r#"echo "it's ${SWAYSTATUS_IP}""#),
+            ("echo $ipv6 $HOME $", "echo $ipv6 $HOME $"),
+            (r"echo \$ip", r"echo \$ip"),
+            (
+                "for name in *; do echo ${name}; done",
+                "for name in *; do echo ${name}; done",
+            ),
+            ("", ""),
+        ];
+        for (cmd, expected) in cases {
+            assert_eq!(substitute_placeholders(cmd, &values), expected, "{}", cmd);
+        }
+    }
+}
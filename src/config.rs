@@ -1,12 +1,20 @@
+use futures::stream::StreamExt;
+use inotify::{Inotify, WatchMask};
 use serde::de::{Deserialize, Deserializer};
 use serde_derive::Deserialize;
 use smartstring::alias::String;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::mpsc;
 use toml::value;
 
 use crate::blocks::BlockType;
+use crate::errors::{Error, OptionExt, Result, ResultExt};
 use crate::icons::Icons;
+use crate::power;
 use crate::themes::Theme;
+use crate::util;
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct SharedConfig {
@@ -16,11 +24,13 @@ pub struct SharedConfig {
     pub icons: Arc<Icons>,
     #[serde(default = "Config::default_icons_format")]
     pub icons_format: Arc<String>,
+    /// Set to `true` to use binary prefixes (`Ki`, `Mi`, ...) for bytes and bits by default
+    #[serde(default)]
+    pub binary_prefixes: bool,
 }
 
 impl SharedConfig {
-    pub fn get_icon(&self, icon: &str) -> crate::errors::Result<String> {
-        use crate::errors::OptionExt;
+    pub fn get_icon(&self, icon: &str) -> Result<String> {
         Ok(self.icons_format.replace(
             "{icon}",
             self.icons
@@ -37,6 +47,7 @@ impl Default for SharedConfig {
             theme: Arc::new(Theme::default()),
             icons: Arc::new(Icons::default()),
             icons_format: Arc::new(" {icon} ".into()),
+            binary_prefixes: false,
         }
     }
 }
@@ -54,6 +65,10 @@ pub struct Config {
     #[serde(default = "Config::default_double_click_delay")]
     pub double_click_delay: u64,
 
+    /// Multiply update intervals of all blocks by this value while on battery
+    #[serde(default = "Config::default_interval_on_battery")]
+    pub interval_on_battery: f64,
+
     #[serde(deserialize_with = "deserialize_blocks")]
     pub block: Vec<(BlockType, value::Value)>,
 }
@@ -66,22 +81,344 @@ impl Config {
     fn default_double_click_delay() -> u64 {
         200
     }
+
+    fn default_interval_on_battery() -> f64 {
+        1.0
+    }
 }
 
-fn deserialize_blocks<'de, D>(deserializer: D) -> Result<Vec<(BlockType, value::Value)>, D::Error>
+fn deserialize_blocks<'de, D>(
+    deserializer: D,
+) -> std::result::Result<Vec<(BlockType, value::Value)>, D::Error>
 where
     D: Deserializer<'de>,
 {
     let mut blocks: Vec<(BlockType, value::Value)> = Vec::new();
     let raw_blocks: Vec<value::Table> = Deserialize::deserialize(deserializer)?;
     for mut entry in raw_blocks {
+        // The name is only used to refer to the block in overrides
+        let block_name = entry.remove("name");
         if let Some(name) = entry.remove("block") {
             let name_str = name.to_string();
             let block = BlockType::deserialize(name)
                 .map_err(|_| serde::de::Error::custom(format!("Unknown block '{}'", name_str)))?;
             blocks.push((block, value::Value::Table(entry)));
+        } else if let Some(block_name) = block_name {
+            // An override that was not merged into any block
+            return Err(serde::de::Error::custom(format!(
+                "Override for unknown block {}",
+                block_name
+            )));
         }
     }
 
     Ok(blocks)
 }
+
+/// Read the config file together with all the files it includes and apply the overrides for the
+/// current host and the selected profile. Returns the merged config and the paths of all the files
+/// that were read.
+///
+/// The files are merged in this order (later ones take precedence):
+/// - the files listed in `include`, in order (recursively)
+/// - the file itself
+/// - the `[hosts.<hostname>]` section matching the current hostname
+/// - the `[profiles.<profile>]` section of the selected profile
+///
+/// Tables are merged key by key and `[[block]]` entries are appended, unless an entry has a `name`
+/// of an already defined block, in which case its keys are merged into that block.
+pub fn load_merged(path: &Path, profile: Option<&str>) -> Result<(value::Value, Vec<PathBuf>)> {
+    let mut files = Vec::new();
+    let mut config = read_with_includes(path, &mut Vec::new(), &mut files)?;
+    let hosts = config.remove("hosts");
+    let profiles = config.remove("profiles");
+
+    if let Some(hosts) = hosts {
+        let mut hosts: value::Table = hosts.try_into().config_error()?;
+        if let Some(host) = hosts.remove(util::hostname()?.as_str()) {
+            let host = host.try_into().error("Host overrides must be a table")?;
+            merge_tables(&mut config, host);
+        }
+    }
+
+    if let Some(profile) = profile {
+        let mut profiles: value::Table = profiles
+            .or_error(|| format!("Profile '{}' not found", profile))?
+            .try_into()
+            .config_error()?;
+        let profile = profiles
+            .remove(profile)
+            .or_error(|| format!("Profile '{}' not found", profile))?
+            .try_into()
+            .or_error(|| format!("Profile '{}' must be a table", profile))?;
+        merge_tables(&mut config, profile);
+    }
+
+    Ok((value::Value::Table(config), files))
+}
+
+/// Same as [`load_merged`], but also deserialize the result
+pub fn load_config(path: &Path, profile: Option<&str>) -> Result<(Config, Vec<PathBuf>)> {
+    let (config, files) = load_merged(path, profile)?;
+    let config = config
+        .try_into()
+        .or_error(|| format!("Failed to deserialize config: {}", path.display()))?;
+    power::check_battery_factor(config.interval_on_battery)?;
+    Ok((config, files))
+}
+
+fn read_with_includes(
+    path: &Path,
+    stack: &mut Vec<PathBuf>,
+    files: &mut Vec<PathBuf>,
+) -> Result<value::Table> {
+    let canonical = path
+        .canonicalize()
+        .or_error(|| format!("Failed to resolve config path: {}", path.display()))?;
+    if stack.contains(&canonical) {
+        return Err(Error::new(format!(
+            "Config file '{}' includes itself",
+            path.display()
+        )));
+    }
+
+    let mut table: value::Table = util::deserialize_toml_file(path)?;
+    let includes: Vec<String> = match table.remove("include") {
+        Some(includes) => includes
+            .try_into()
+            .or_error(|| format!("'include' must be a list of paths in {}", path.display()))?,
+        None => Vec::new(),
+    };
+
+    files.push(canonical.clone());
+    stack.push(canonical);
+    // Paths are relative to the including file
+    let dir = path.parent().unwrap_or_else(|| Path::new(""));
+    let mut merged = value::Table::new();
+    for include in includes {
+        let include_path = dir.join(&*shellexpand::tilde(include.as_str()));
+        let included = read_with_includes(&include_path, stack, files)
+            .or_error(|| format!("Failed to include '{}' in {}", include, path.display()))?;
+        merge_tables(&mut merged, included);
+    }
+    stack.pop();
+
+    merge_tables(&mut merged, table);
+    Ok(merged)
+}
+
+fn merge_tables(base: &mut value::Table, other: value::Table) {
+    for (key, val) in other {
+        if !base.contains_key(&key) {
+            base.insert(key, val);
+            continue;
+        }
+        match (base.get_mut(&key).unwrap(), val) {
+            (value::Value::Array(blocks), value::Value::Array(new)) if key == "block" => {
+                merge_blocks(blocks, new)
+            }
+            (slot, val) => merge_values(slot, val),
+        }
+    }
+}
+
+fn merge_values(base: &mut value::Value, other: value::Value) {
+    match (base, other) {
+        (value::Value::Table(base), value::Value::Table(other)) => merge_tables(base, other),
+        (base, other) => *base = other,
+    }
+}
+
+fn merge_blocks(blocks: &mut Vec<value::Value>, new: Vec<value::Value>) {
+    for block in new {
+        let existing = block_name(&block)
+            .and_then(|name| blocks.iter().position(|b| block_name(b) == Some(name)));
+        match existing {
+            Some(pos) => merge_values(&mut blocks[pos], block),
+            None => blocks.push(block),
+        }
+    }
+}
+
+fn block_name(block: &value::Value) -> Option<&str> {
+    block.get("name").and_then(value::Value::as_str)
+}
+
+/// Spawn a task that watches the config files and sends a message on the returned channel every
+/// time one of them is changed.
+pub fn config_updates_stream(paths: &[PathBuf]) -> Result<mpsc::Receiver<()>> {
+    let mut notify = Inotify::init().error("Failed to start inotify")?;
+    let mut watched = Vec::with_capacity(paths.len());
+    for path in paths {
+        // Watch the parent directory, because most editors replace the file instead of writing to it
+        let path = path.canonicalize().error("Failed to resolve config path")?;
+        let dir = path.parent().error("Config path has no parent directory")?;
+        let file_name = path
+            .file_name()
+            .error("Config path has no file name")?
+            .to_owned();
+        let watch = notify
+            .add_watch(
+                dir,
+                WatchMask::CLOSE_WRITE | WatchMask::MOVED_TO | WatchMask::CREATE,
+            )
+            .error("Failed to watch config directory")?;
+        watched.push((watch, file_name));
+    }
+    let mut updates = notify
+        .event_stream([0; 1024])
+        .error("Failed to create event stream")?;
+
+    let (tx, rx) = mpsc::channel(1);
+
+    tokio::spawn(async move {
+        loop {
+            let event = tokio::select! {
+                event = updates.next() => match event {
+                    Some(Ok(event)) => event,
+                    _ => break,
+                },
+                // The files are watched again after a reload that changed them
+                _ = tx.closed() => break,
+            };
+            let is_config = watched.iter().any(|(watch, file_name)| {
+                event.wd == *watch && event.name.as_deref() == Some(file_name.as_os_str())
+            });
+            if !is_config {
+                continue;
+            }
+
+            // Editors tend to produce several events per save, so wait a bit and coalesce them
+            tokio::time::sleep(Duration::from_millis(100)).await;
+            if let Err(mpsc::error::TrySendError::Closed(_)) = tx.try_send(()) {
+                // Receiver is dropped - no need to loop anymore
+                break;
+            }
+        }
+    });
+
+    Ok(rx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+
+    fn table(s: &str) -> value::Table {
+        toml::from_str(s).unwrap()
+    }
+
+    #[test]
+    fn test_merge_tables() {
+        let mut base = table(
+            r##"
+            icons = "awesome"
+            [theme]
+            name = "solarized-dark"
+            [theme.overrides]
+            idle_bg = "#123456"
+            "##,
+        );
+        merge_tables(
+            &mut base,
+            table(
+                r##"
+                icons = "material"
+                invert_scrolling = true
+                [theme.overrides]
+                idle_fg = "#abcdef"
+                "##,
+            ),
+        );
+        let expected = table(
+            r##"
+            icons = "material"
+            invert_scrolling = true
+            [theme]
+            name = "solarized-dark"
+            [theme.overrides]
+            idle_bg = "#123456"
+            idle_fg = "#abcdef"
+            "##,
+        );
+        assert_eq!(base, expected);
+    }
+
+    #[test]
+    fn test_merge_blocks() {
+        let mut base = table(
+            r#"
+            [[block]]
+            block = "cpu"
+            name = "cpu"
+            interval = 1
+            [[block]]
+            block = "time"
+            "#,
+        );
+        merge_tables(
+            &mut base,
+            table(
+                r#"
+                [[block]]
+                name = "cpu"
+                interval = 5
+                [[block]]
+                block = "memory"
+                name = "mem"
+                "#,
+            ),
+        );
+        let expected = table(
+            r#"
+            [[block]]
+            block = "cpu"
+            name = "cpu"
+            interval = 5
+            [[block]]
+            block = "time"
+            [[block]]
+            block = "memory"
+            name = "mem"
+            "#,
+        );
+        assert_eq!(base, expected);
+    }
+
+    #[test]
+    fn test_override_for_unknown_block() {
+        let config = table(
+            r#"
+            [[block]]
+            name = "cpu"
+            interval = 5
+            "#,
+        );
+        let error = value::Value::Table(config)
+            .try_into::<Config>()
+            .unwrap_err();
+        assert!(error.to_string().contains("Override for unknown block"));
+    }
+
+    #[test]
+    fn test_include_cycle() {
+        let dir = std::env::temp_dir().join(format!("swaystatus-include-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("a.toml"), "include = [\"b.toml\"]\n").unwrap();
+        fs::write(dir.join("b.toml"), "include = [\"a.toml\"]\n").unwrap();
+        fs::write(dir.join("c.toml"), "include = [\"d.toml\"]\nx = 1\n").unwrap();
+        fs::write(dir.join("d.toml"), "x = 0\ny = 2\n").unwrap();
+
+        let error =
+            read_with_includes(&dir.join("a.toml"), &mut Vec::new(), &mut Vec::new()).unwrap_err();
+        assert!(error.to_string().contains("includes itself"));
+
+        let mut files = Vec::new();
+        let merged = read_with_includes(&dir.join("c.toml"), &mut Vec::new(), &mut files).unwrap();
+        assert_eq!(merged, table("x = 1\ny = 2\n"));
+        assert_eq!(files.len(), 2);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -0,0 +1,162 @@
+//! Bar-level DBus control interface
+//!
+//! With the `--control` flag, swaystatus exposes `/` object in `rs.swaystatus` service. This
+//! object implements `rs.swaystatus.bar` interface, which allows to control the bar as a whole.
+//! When several bars are running, give each one its own service name with `--dbus-name`, e.g.
+//! `--control --dbus-name rs.swaystatus.top`.
+//!
+//! Output of `busctl --user introspect rs.swaystatus / rs.swaystatus.bar`:
+//! ```text
+//! NAME                                TYPE      SIGNATURE RESULT/VALUE FLAGS
+//! rs.swaystatus.bar                   interface -         -            -
+//! .Click                              method    us        s            -
+//! .ClickInstance                      method    uus       s            -
+//! .Dump                               method    -         s            -
+//! .Hide                               method    u         s            -
+//! .ListBlocks                         method    -         a(uss)       -
+//! .Show                               method    u         s            -
+//! .ToggleFullScreen                   method    u         s            -
+//! .Update                             method    u         s            -
+//! ```
+//!
+//! Blocks are identified by their IDs, which can be obtained with `ListBlocks`. It returns the
+//! ID, the type and the status ("running" or "failed") of every block.
+//!
+//! # Example
+//!
+//! ```sh
+//! # list blocks
+//! busctl --user call rs.swaystatus / rs.swaystatus.bar ListBlocks
+//! # hide block with ID 3
+//! busctl --user call rs.swaystatus / rs.swaystatus.bar Hide u 3
+//! # right click on block with ID 3 (modifiers can be added, e.g. "Shift+right")
+//! busctl --user call rs.swaystatus / rs.swaystatus.bar Click us 3 right
+//! # print currently displayed blocks, the way they are sent to i3bar
+//! busctl --user call rs.swaystatus / rs.swaystatus.bar Dump
+//! ```
+
+use smartstring::alias::String;
+use std::string::String as StdString;
+use tokio::sync::mpsc;
+use tokio::sync::oneshot;
+use zbus::dbus_interface;
+
+use crate::click::{ClickButton, MouseButton};
+use crate::errors::*;
+use crate::protocol::i3bar_event::I3BarEvent;
+
+/// A block as reported by `ListBlocks`: ID, type and status
+pub type BlockInfo = (u32, StdString, StdString);
+
+#[derive(Debug)]
+pub enum ControlCmd {
+    ListBlocks(oneshot::Sender<Vec<BlockInfo>>),
+    SetHidden(usize, bool, oneshot::Sender<Result<()>>),
+    Update(usize, oneshot::Sender<Result<()>>),
+    Click(I3BarEvent, oneshot::Sender<Result<()>>),
+    ToggleFullScreen(usize, oneshot::Sender<Result<()>>),
+    Dump(oneshot::Sender<Result<String>>),
+}
+
+struct Control {
+    tx: mpsc::Sender<ControlCmd>,
+}
+
+impl Control {
+    async fn call<T>(&self, cmd: impl FnOnce(oneshot::Sender<T>) -> ControlCmd) -> Result<T> {
+        let (tx, rx) = oneshot::channel();
+        self.tx
+            .send(cmd(tx))
+            .await
+            .error("Failed to send control command")?;
+        rx.await.error("Failed to receive control response")
+    }
+
+    async fn call_ok(
+        &self,
+        cmd: impl FnOnce(oneshot::Sender<Result<()>>) -> ControlCmd,
+    ) -> StdString {
+        match self.call(cmd).await {
+            Ok(Ok(())) => "OK".into(),
+            Ok(Err(e)) | Err(e) => e.to_string(),
+        }
+    }
+}
+
+#[dbus_interface(name = "rs.swaystatus.bar")]
+impl Control {
+    async fn list_blocks(&self) -> Vec<BlockInfo> {
+        self.call(ControlCmd::ListBlocks).await.unwrap_or_default()
+    }
+
+    async fn hide(&self, block: u32) -> StdString {
+        self.call_ok(|tx| ControlCmd::SetHidden(block as usize, true, tx))
+            .await
+    }
+
+    async fn show(&self, block: u32) -> StdString {
+        self.call_ok(|tx| ControlCmd::SetHidden(block as usize, false, tx))
+            .await
+    }
+
+    async fn update(&self, block: u32) -> StdString {
+        self.call_ok(|tx| ControlCmd::Update(block as usize, tx))
+            .await
+    }
+
+    async fn click(&self, block: u32, button: &str) -> StdString {
+        self.click_instance_opt(block, None, button).await
+    }
+
+    async fn click_instance(&self, block: u32, instance: u32, button: &str) -> StdString {
+        self.click_instance_opt(block, Some(instance as usize), button)
+            .await
+    }
+
+    async fn toggle_full_screen(&self, block: u32) -> StdString {
+        self.call_ok(|tx| ControlCmd::ToggleFullScreen(block as usize, tx))
+            .await
+    }
+
+    async fn dump(&self) -> StdString {
+        match self.call(ControlCmd::Dump).await {
+            Ok(Ok(json)) => json.into(),
+            Ok(Err(e)) | Err(e) => e.to_string(),
+        }
+    }
+}
+
+impl Control {
+    async fn click_instance_opt(
+        &self,
+        block: u32,
+        instance: Option<usize>,
+        button: &str,
+    ) -> StdString {
+        let button: ClickButton = match button.parse() {
+            Ok(button) => button,
+            Err(e) => return e.to_string(),
+        };
+        if button.button == MouseButton::Unknown {
+            return "Unknown button".into();
+        }
+        let event = I3BarEvent {
+            id: block as usize,
+            instance,
+            button: button.button,
+            clicks: button.clicks,
+            modifiers: button.modifiers,
+            ..Default::default()
+        };
+        self.call_ok(|tx| ControlCmd::Click(event, tx)).await
+    }
+}
+
+/// Serve the control interface on the given connection. The commands are sent to `tx`.
+pub async fn serve(conn: &zbus::Connection, tx: mpsc::Sender<ControlCmd>) -> Result<()> {
+    conn.object_server()
+        .at("/", Control { tx })
+        .await
+        .error("Failed to setup DBus control interface")?;
+    Ok(())
+}
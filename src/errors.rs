@@ -3,6 +3,7 @@ use std::fmt;
 use std::sync::Arc;
 
 use crate::blocks::BlockType;
+use crate::formatting::template::ParseError;
 
 pub use std::error::Error as StdError;
 pub use std::result::Result as StdResult;
@@ -53,12 +54,16 @@ pub trait InBlock {
     fn in_block(self, block: BlockType, block_id: usize) -> Self;
 }
 
+impl InBlock for Error {
+    fn in_block(mut self, block: BlockType, block_id: usize) -> Self {
+        self.block = Some((block, block_id));
+        self
+    }
+}
+
 impl<T> InBlock for Result<T> {
     fn in_block(self, block: BlockType, block_id: usize) -> Self {
-        self.map_err(|mut e| {
-            e.block = Some((block, block_id));
-            e
-        })
+        self.map_err(|e| e.in_block(block, block_id))
     }
 }
 
@@ -167,15 +172,11 @@ impl fmt::Display for Error {
                     write!(f, ": {}", message)?;
                 }
 
-                if let Some(cause) = &self.cause {
-                    write!(f, ". (Cause: {})", cause)?;
-                }
+                self.fmt_cause(f)?;
             }
             None => {
                 f.write_str(self.message.as_deref().unwrap_or("Error"))?;
-                if let Some(cause) = &self.cause {
-                    write!(f, ". (Cause: {})", cause)?;
-                }
+                self.fmt_cause(f)?;
             }
         }
 
@@ -183,6 +184,17 @@ impl fmt::Display for Error {
     }
 }
 
+impl Error {
+    fn fmt_cause(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match &self.cause {
+            // The message already tells where the format template is broken
+            Some(cause) if cause.is::<ParseError>() => Ok(()),
+            Some(cause) => write!(f, ". (Cause: {})", cause),
+            None => Ok(()),
+        }
+    }
+}
+
 impl StdError for Error {}
 
 pub trait ToSerdeError<T> {
@@ -32,6 +32,47 @@ impl<I: Iterator<Item = char>> CollectEscaped for I {
     }
 }
 
+/// Remove pango tags from `text` and unescape the entities
+pub fn strip_pango(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '<' => {
+                for c in chars.by_ref() {
+                    if c == '>' {
+                        break;
+                    }
+                }
+            }
+            '&' => {
+                let entity: String = chars.by_ref().take_while(|&c| c != ';').collect();
+                let unescaped = match entity.as_str() {
+                    "amp" => Some('&'),
+                    "lt" => Some('<'),
+                    "gt" => Some('>'),
+                    "quot" => Some('"'),
+                    "apos" => Some('\''),
+                    x => x
+                        .strip_prefix("#x")
+                        .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                        .or_else(|| x.strip_prefix('#').and_then(|dec| dec.parse().ok()))
+                        .and_then(char::from_u32),
+                };
+                match unescaped {
+                    Some(c) => out.push(c),
+                    None => {
+                        out.push('&');
+                        out.push_str(&entity);
+                    }
+                }
+            }
+            x => out.push(x),
+        }
+    }
+    out
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -42,4 +83,10 @@ mod tests {
         let escaped: String = orig.chars().collect_pango();
         assert_eq!(escaped, "&amp;my &#39;text&#39; &lt;&gt;");
     }
+
+    #[test]
+    fn strip() {
+        let markup = "<span foreground='red'>&amp;my</span> &#39;text&#39; &lt;&gt;";
+        assert_eq!(strip_pango(markup), "&my 'text' <>");
+    }
 }
@@ -14,6 +14,9 @@
 //! --------------------------|------------------
 //! Text                      | `str`
 //! Number                    | `eng`
+//! Duration                  | `dur`
+//! Timestamp                 | `datetime`
+//! [List](#lists)            | `join`
 //! [Flag](#how-to-use-flags) | N/A
 //!
 //! # Formatters
@@ -36,13 +39,76 @@
 //! Width - if text is shorter it will be paded using spaces | `15`
 //! Interval - If text is longer than `width` it will be rotated every `interval` seconds | `1.0`
 //!
+//! ## `graph` - Display the history of a number as a sparkline
+//!
+//! The last `width` values of the placeholder are kept while the block is running, so any
+//! numeric placeholder can be shown as a graph, e.g. `$utilization.graph(10)`. Expressions can be
+//! graphed too (`$($rx + $tx).graph()`), and so can the items of a list inside `each`
+//! (`$utilizations.each({$item.graph(5)})`), which keeps a history for every index of the list.
+//!
+//! Argument | Default value
+//! ---------|--------------
+//! Width - the number of values to display | `10`
+//!
 //! ## `eng` - Format numbers using engineering notation
 //!
 //! Argument | Default value
 //! ---------|--------------
 //! Width - the resulting text will be at least `width` characters long | `2`
-//! Unit - some values have a [unit](unit::Unit), and it is possible to convert them by setting this option. Perpend this with a space to split unit from number/prefix. Prepend this with a `_` to hide. | `auto`
-//! Prefix - specifiy this argument if you want to set the minimal [SI prefix](prefix::Prefix). Prepend this width a space to split prefix from number. Perpend this with a `_` to hide. Perpend this with a `!` to force the prefix. | `auto`
+//! Unit - some values have a [unit](unit::Unit), and it is possible to convert them by setting this option. Supported conversions: `B` <-> `b`, `deg` (°C) <-> `degF` <-> `K`, `m/s` <-> `km/h` <-> `mph` and `s` <-> `min` <-> `h`. Perpend this with a space to split unit from number/prefix. Prepend this with a `_` to hide. | `auto`
+//! Prefix - specifiy this argument if you want to set the minimal [SI or binary prefix](prefix::Prefix). Binary prefixes (`Ki`, `Mi`, `Gi`, `Ti`) are used by default for bytes and bits if `binary_prefixes` is set to `true` in the top-level config. Prepend this width a space to split prefix from number. Perpend this with a `_` to hide. Perpend this with a `!` to force the prefix. | `auto`
+//!
+//! ## `fix` - Format numbers using fixed-point notation
+//!
+//! Argument | Default value
+//! ---------|--------------
+//! Precision - the number of digits after the decimal point | `1`
+//! Unit - same as for `eng` | `auto`
+//! Prefix - the [SI or binary prefix](prefix::Prefix) to always use. Prepend this width a space to split prefix from number. Perpend this with a `_` to hide. The prefix is always forced, so `!` is not allowed. | `1`
+//!
+//! ## `dur` - Format durations
+//!
+//! Argument | Default value
+//! ---------|--------------
+//! Style - one of `hms` (`1:02:03`), `hm` (`1:02`), `min` (`63 min`, rounded up) or `compact` (two biggest units, e.g. `1h 2m`) | `compact`
+//!
+//! ## `datetime` - Format timestamps
+//!
+//! Argument | Default value
+//! ---------|--------------
+//! Format - see [chrono docs](https://docs.rs/chrono/latest/chrono/format/strftime/index.html) for all options | `%a %d/%m %R`
+//!
+//! ## `join` - Join the items of a list
+//!
+//! Every item is formatted using the default formatter of its type.
+//!
+//! Argument | Default value
+//! ---------|--------------
+//! Separator - the text to put between the items | ` `
+//!
+//! ## `count` - The number of items in a list
+//!
+//! No arguments.
+//!
+//! ## `first` - The first item of a list
+//!
+//! No arguments. Fails if the list is empty.
+//!
+//! ## `at` - The item of a list at the given index
+//!
+//! Argument | Default value
+//! ---------|--------------
+//! Index - starting from `0`. Fails if the list is too short. | N/A
+//!
+//! ## `each` - Format every item of a list using a template
+//!
+//! The first argument is a format template in `{}`. Inside of it the item is available as `$item`
+//! and its index as `$index`.
+//!
+//! Argument | Default value
+//! ---------|--------------
+//! Template | N/A
+//! Separator - the text to put between the items | empty
 //!
 //! # Handling missing placeholders and incorrect types
 //!
@@ -63,10 +129,50 @@
 //! general, flags are used in this way:
 //!
 //! ```text
-//! $a{a is set}|$b$c{b and c are set}|${b|c}{b or c is set}|neither flag is set
+//! $a{a is set}|$b$c{b and c are set}|{$b|$c}{b or c is set}|neither flag is set
+//! ```
+//!
+//! `${b|c}` is an older spelling of `{$b|$c}` and still works.
+//!
+//! # Conditions
+//!
+//! Numeric placeholders can be compared using `${<lhs> <op> <rhs>}`, where both sides are either
+//! placeholders or numbers and `<op>` is one of `<`, `<=`, `>`, `>=`, `==` or `!=`. Like flags, a
+//! condition renders nothing if it holds and makes the current alternative fail otherwise:
+//!
+//! ```text
+//! ${$percentage < 20}{LOW $percentage}|$percentage
+//! ```
+//!
+//! Note that the raw value is compared, without unit conversion or prefixes (e.g. memory is
+//! compared in bytes). Durations can be compared too, in seconds.
+//!
+//! # Expressions
+//!
+//! Numbers can be computed from placeholders using `$(<expression>)`, optionally followed by a
+//! formatter like a regular placeholder. Expressions support `+`, `-`, `*`, `/`, parentheses and
+//! the functions `min`, `max`, `round`, `floor`, `ceil` and `abs`:
+//!
+//! ```text
+//! $($mem_total - $mem_avail).eng(3,B,M) $(max($temp, $apparent))
+//! ```
+//!
+//! The result keeps the unit of its operands: values with convertible units (e.g. `B` and `b`) can
+//! be added or subtracted, and multiplying or dividing by a plain number keeps the unit.
+//! Temperatures in different units can't be added or subtracted, since their scales start at
+//! different points. Durations are treated as seconds. Both sides of a [condition](#conditions) may be expressions too.
+//!
+//! # Lists
+//!
+//! Some blocks provide lists of values, e.g. [cpu](crate::blocks::cpu)'s `utilizations`. Lists
+//! can be rendered with the `join`, `count`, `first`, `at` and `each` formatters:
+//!
+//! ```text
+//! $utilizations.each({$index: $item.eng(2)},\, )
 //! ```
 
 pub mod config;
+pub mod expr;
 pub mod formatter;
 pub mod prefix;
 pub mod template;
@@ -74,14 +180,16 @@ pub mod unit;
 pub mod value;
 
 use smartstring::alias::String;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
 use std::sync::Arc;
 
 use tokio::sync::mpsc::Sender;
 use tokio::task::JoinHandle;
 
+use crate::config::SharedConfig;
 use crate::errors::*;
 use crate::Request;
+use expr::Expr;
 use template::FormatTemplate;
 use value::Value;
 
@@ -97,11 +205,22 @@ impl Format {
         if let Some(short) = &self.0 .1 {
             short.init(tx, block_id, &mut handles);
         }
-        RunningFormat(self, handles)
+        let history = self.new_history();
+        RunningFormat(self, handles, history)
     }
 
     pub fn run_no_init(self) -> RunningFormat {
-        RunningFormat(self, Handles::default())
+        let history = self.new_history();
+        RunningFormat(self, Handles::default(), history)
+    }
+
+    fn new_history(&self) -> History {
+        let mut history = History::default();
+        self.0 .0.track_history(&mut history);
+        if let Some(short) = &self.0 .1 {
+            short.track_history(&mut history);
+        }
+        history
     }
 
     pub fn contains_key(&self, key: &str) -> bool {
@@ -120,17 +239,208 @@ impl Drop for Handles {
     }
 }
 
+/// Previous values of the placeholders and expressions which are formatted with a formatter that
+/// needs them (e.g. `graph`)
+#[derive(Debug, Default, Clone)]
+pub struct History {
+    /// Maps the key of a placeholder or an expression to the expression which computes it, the
+    /// maximum length and the values
+    values: HashMap<String, (Expr, usize, VecDeque<f64>)>,
+    /// Maps the name of a list formatted with `each` to the history of a single item and the
+    /// histories of the items by their index
+    items: HashMap<String, (Box<History>, Vec<History>)>,
+}
+
+impl History {
+    pub fn get(&self, key: &str) -> Option<&VecDeque<f64>> {
+        self.values.get(key).map(|(_, _, values)| values)
+    }
+
+    /// The histories of the items of a list, by their index
+    pub fn get_items(&self, name: &str) -> Option<&[History]> {
+        self.items.get(name).map(|(_, items)| items.as_slice())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty() && self.items.is_empty()
+    }
+
+    /// Keep the last `len` values of `expr` under `key`
+    pub fn track(&mut self, key: String, expr: Expr, len: usize) {
+        let (_, max_len, _) = self
+            .values
+            .entry(key)
+            .or_insert_with(|| (expr, 0, VecDeque::new()));
+        *max_len = (*max_len).max(len);
+    }
+
+    /// Keep a history like `item` for every item of the list `name`
+    pub fn track_items(&mut self, name: String, item: History) {
+        let (tracked, _) = self.items.entry(name).or_default();
+        for (key, (expr, len, _)) in item.values {
+            tracked.track(key, expr, len);
+        }
+        for (name, (item, _)) in item.items {
+            tracked.track_items(name, *item);
+        }
+    }
+
+    fn push(&mut self, vars: &Values) {
+        for (expr, len, values) in self.values.values_mut() {
+            if let Ok((val, _)) = expr.eval(vars) {
+                if values.len() == *len {
+                    values.pop_front();
+                }
+                values.push_back(val);
+            }
+        }
+        for (name, (item, histories)) in &mut self.items {
+            if let Some(Value::List(list)) = vars.get(name) {
+                histories.truncate(list.len());
+                while histories.len() < list.len() {
+                    histories.push((**item).clone());
+                }
+                for (i, (value, history)) in list.iter().zip(histories).enumerate() {
+                    history.push(&item_values(value, i));
+                }
+            }
+        }
+    }
+
+    fn inherit(&mut self, old: &mut History) {
+        for (key, (_, len, values)) in &mut self.values {
+            if let Some((_, _, mut old_values)) = old.values.remove(key) {
+                while old_values.len() > *len {
+                    old_values.pop_front();
+                }
+                *values = old_values;
+            }
+        }
+        for (name, (item, histories)) in &mut self.items {
+            if let Some((_, old_histories)) = old.items.remove(name) {
+                *histories = old_histories
+                    .into_iter()
+                    .map(|mut old_history| {
+                        let mut history = (**item).clone();
+                        history.inherit(&mut old_history);
+                        history
+                    })
+                    .collect();
+            }
+        }
+    }
+}
+
+/// The placeholders available to the template of `each`
+fn item_values(item: &Value, index: usize) -> Values {
+    map! {
+        "item" => item.clone(),
+        "index" => Value::number(index),
+    }
+}
+
 #[derive(Debug)]
-pub struct RunningFormat(Format, Handles);
+pub struct RunningFormat(Format, Handles, History);
 
 impl RunningFormat {
-    pub fn render(&self, vars: &Values) -> Result<(String, Option<String>)> {
+    pub fn render(&self, vars: &Values, config: &SharedConfig) -> Result<(String, Option<String>)> {
         let (full, short) = self.0 .0.as_ref();
-        let full = full.render(vars).error("Failed to render full text")?;
+        let full = full
+            .render(vars, &self.2, config)
+            .error("Failed to render full text")?;
         let short = match short {
-            Some(short) => Some(short.render(vars).error("Failed to render short text")?),
+            Some(short) => Some(
+                short
+                    .render(vars, &self.2, config)
+                    .error("Failed to render short text")?,
+            ),
             None => None,
         };
         Ok((full, short))
     }
+
+    /// Record the numeric values which are displayed with a history
+    pub fn push_history(&mut self, vars: &Values) {
+        self.2.push(vars);
+    }
+
+    /// Keep the history of the previous format, so it's not lost when a block changes its format
+    pub fn inherit_history(&mut self, old: &mut RunningFormat) {
+        self.2.inherit(&mut old.2);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(format: &str) -> RunningFormat {
+        config::Config::default()
+            .with_default(format)
+            .unwrap()
+            .run_no_init()
+    }
+
+    fn render(format: &RunningFormat, vars: &Values) -> std::string::String {
+        format
+            .render(vars, &SharedConfig::default())
+            .unwrap()
+            .0
+            .to_string()
+    }
+
+    fn push(format: &mut RunningFormat, vars: Values) -> Values {
+        format.push_history(&vars);
+        vars
+    }
+
+    #[test]
+    fn history() {
+        let mut format = run("$a.graph(3)");
+        for a in [1, 2, 3] {
+            push(&mut format, map! { "a" => Value::number(a) });
+        }
+        let vars = push(&mut format, map! { "a" => Value::number(4) });
+        assert_eq!(render(&format, &vars), "▁▄█");
+
+        // Missing and non-numeric values are not recorded
+        push(&mut format, map! { "b" => Value::number(0) });
+        let vars = push(&mut format, map! { "a" => Value::text("x".into()) });
+        assert_eq!(render(&format, &map! { "a" => Value::number(4) }), "▁▄█");
+        assert!(format.render(&vars, &SharedConfig::default()).is_err());
+    }
+
+    #[test]
+    fn expression_history() {
+        let mut format = run("$($a * 2).graph(3)");
+        push(&mut format, map! { "a" => Value::number(1) });
+        let vars = push(&mut format, map! { "a" => Value::number(2) });
+        assert_eq!(render(&format, &vars), " ▁█");
+    }
+
+    #[test]
+    fn item_history() {
+        let list = |items: &[i32]| Value::list(items.iter().map(|&i| Value::number(i)).collect());
+        let mut format = run("$l.each({$item.graph(2)}, )");
+        push(&mut format, map! { "l" => list(&[1, 5]) });
+        let vars = push(&mut format, map! { "l" => list(&[2, 3]) });
+        assert_eq!(render(&format, &vars), "▁█ █▁");
+
+        // Items which are gone lose their history, new ones start without one
+        let vars = push(&mut format, map! { "l" => list(&[7]) });
+        assert_eq!(render(&format, &vars), "▁█");
+        let vars = push(&mut format, map! { "l" => list(&[8, 1]) });
+        assert_eq!(render(&format, &vars), "▁█  ▁");
+    }
+
+    #[test]
+    fn inherit_history() {
+        let mut old = run("$a.graph(3)");
+        for a in [1, 2, 3] {
+            push(&mut old, map! { "a" => Value::number(a) });
+        }
+        let mut format = run("$a.graph(2)");
+        format.inherit_history(&mut old);
+        assert_eq!(render(&format, &map! { "a" => Value::number(3) }), "▁█");
+    }
 }
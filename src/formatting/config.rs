@@ -1,24 +1,44 @@
 use super::{template::FormatTemplate, Format};
-use crate::errors::ToSerdeError;
 use serde::de::{MapAccess, Visitor};
 use serde::{de, Deserialize, Deserializer};
 use smartstring::alias::String;
 use std::fmt;
 use std::sync::Arc;
 
-#[derive(Debug, Default)]
+/// The templates are kept unparsed until `with_default` is called, so that parse errors are
+/// reported with their position in the block that failed rather than as a generic config error.
+#[derive(Debug, Default, Clone)]
 pub struct Config {
-    full: Option<FormatTemplate>,
-    short: Option<FormatTemplate>,
+    full: Option<String>,
+    short: Option<String>,
 }
 
 impl Config {
     pub fn with_default(self, default_full: &str) -> crate::errors::Result<Format> {
-        let full = match self.full {
-            Some(full) => full,
-            None => default_full.parse()?,
-        };
-        Ok(Format(Arc::new((full, self.short))))
+        let full: FormatTemplate = self.full.as_deref().unwrap_or(default_full).parse()?;
+        let short = self.short.as_deref().map(str::parse).transpose()?;
+        Ok(Format(Arc::new((full, short))))
+    }
+}
+
+/// Format configs which can be checked without running the block (see `--check`)
+pub trait CheckFormat {
+    /// Parse the template(s), using `default_full` if no full template is set
+    fn check(&self, default_full: &str) -> crate::errors::Result<()>;
+}
+
+impl CheckFormat for Config {
+    fn check(&self, default_full: &str) -> crate::errors::Result<()> {
+        self.clone().with_default(default_full).map(drop)
+    }
+}
+
+impl CheckFormat for Option<Config> {
+    fn check(&self, default_full: &str) -> crate::errors::Result<()> {
+        match self {
+            Some(config) => config.check(default_full),
+            None => Ok(()),
+        }
     }
 }
 
@@ -53,7 +73,7 @@ impl<'de> Deserialize<'de> for Config {
                 E: de::Error,
             {
                 Ok(Config {
-                    full: Some(full.parse().serde_error()?),
+                    full: Some(full.into()),
                     short: None,
                 })
             }
@@ -69,21 +89,21 @@ impl<'de> Deserialize<'de> for Config {
             where
                 V: MapAccess<'de>,
             {
-                let mut full: Option<FormatTemplate> = None;
-                let mut short: Option<FormatTemplate> = None;
+                let mut full: Option<String> = None;
+                let mut short: Option<String> = None;
                 while let Some(key) = map.next_key()? {
                     match key {
                         Field::Full => {
                             if full.is_some() {
                                 return Err(de::Error::duplicate_field("full"));
                             }
-                            full = Some(map.next_value::<String>()?.parse().serde_error()?);
+                            full = Some(map.next_value()?);
                         }
                         Field::Short => {
                             if short.is_some() {
                                 return Err(de::Error::duplicate_field("short"));
                             }
-                            short = Some(map.next_value::<String>()?.parse().serde_error()?);
+                            short = Some(map.next_value()?);
                         }
                     }
                 }
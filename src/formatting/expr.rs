@@ -0,0 +1,313 @@
+//! Arithmetic expressions, e.g. `$mem_total - $mem_avail` or `max($a, $b) / 2`
+
+use smartstring::alias::String;
+use std::collections::HashMap;
+use std::iter::Peekable;
+use std::str::{Chars, FromStr};
+
+use super::unit::Unit;
+use super::value::Value;
+use crate::errors::*;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Number(f64),
+    Var(String),
+    Neg(Box<Expr>),
+    BinOp(Box<Expr>, BinOp, Box<Expr>),
+    Func(Func, Vec<Expr>),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Func {
+    Min,
+    Max,
+    Round,
+    Floor,
+    Ceil,
+    Abs,
+}
+
+impl Expr {
+    pub fn contains_key(&self, key: &str) -> bool {
+        match self {
+            Self::Number(_) => false,
+            Self::Var(name) => name == key,
+            Self::Neg(expr) => expr.contains_key(key),
+            Self::BinOp(lhs, _, rhs) => lhs.contains_key(key) || rhs.contains_key(key),
+            Self::Func(_, args) => args.iter().any(|arg| arg.contains_key(key)),
+        }
+    }
+
+    /// Evaluate the expression into a number with a unit
+    pub fn eval(&self, vars: &HashMap<String, Value>) -> Result<(f64, Unit)> {
+        match self {
+            Self::Number(val) => Ok((*val, Unit::None)),
+            Self::Var(name) => match vars.get(name) {
+                Some(Value::Number { val, unit, .. }) => Ok((*val, *unit)),
+                Some(Value::Duration(dur)) => Ok((dur.as_secs_f64(), Unit::Seconds)),
+                Some(_) => Err(Error::new_format(format!(
+                    "Placeholder '{}' is not a number",
+                    name
+                ))),
+                None => Err(Error::new_format(format!(
+                    "Placeholder with name '{}' not found",
+                    name
+                ))),
+            },
+            Self::Neg(expr) => expr.eval(vars).map(|(val, unit)| (-val, unit)),
+            Self::BinOp(lhs, op, rhs) => {
+                let lhs = lhs.eval(vars)?;
+                let rhs = rhs.eval(vars)?;
+                op.apply(lhs, rhs)
+            }
+            Self::Func(func, args) => {
+                let mut args = args.iter().map(|arg| arg.eval(vars));
+                let first = args.next().error("Function requires an argument")??;
+                match func {
+                    Func::Min | Func::Max => args.try_fold(first, |(acc, acc_unit), arg| {
+                        let (val, unit) = arg?;
+                        let val = same_unit(acc_unit, val, unit)?;
+                        let val = match func {
+                            Func::Min => acc.min(val),
+                            _ => acc.max(val),
+                        };
+                        Ok((val, unit_or(acc_unit, unit)))
+                    }),
+                    Func::Round => Ok((first.0.round(), first.1)),
+                    Func::Floor => Ok((first.0.floor(), first.1)),
+                    Func::Ceil => Ok((first.0.ceil(), first.1)),
+                    Func::Abs => Ok((first.0.abs(), first.1)),
+                }
+            }
+        }
+    }
+
+    /// Evaluate the expression into a [`Value::Number`]
+    pub fn eval_value(&self, vars: &HashMap<String, Value>) -> Result<Value> {
+        let (val, unit) = self.eval(vars)?;
+        Ok(Value::number_unit(val, unit))
+    }
+}
+
+impl BinOp {
+    /// Sums and differences keep the unit of the operands (converting the right one if needed,
+    /// except for temperatures).
+    /// Products and quotients keep the unit only if the other operand has none.
+    fn apply(
+        self,
+        (lhs, lhs_unit): (f64, Unit),
+        (rhs, rhs_unit): (f64, Unit),
+    ) -> Result<(f64, Unit)> {
+        match self {
+            Self::Add => Ok((
+                lhs + summand(lhs_unit, rhs, rhs_unit)?,
+                unit_or(lhs_unit, rhs_unit),
+            )),
+            Self::Sub => Ok((
+                lhs - summand(lhs_unit, rhs, rhs_unit)?,
+                unit_or(lhs_unit, rhs_unit),
+            )),
+            Self::Mul => Ok((
+                lhs * rhs,
+                match (lhs_unit, rhs_unit) {
+                    (Unit::None, unit) | (unit, Unit::None) => unit,
+                    _ => Unit::None,
+                },
+            )),
+            Self::Div => {
+                let (rhs, unit) = match (lhs_unit, rhs_unit) {
+                    (unit, Unit::None) => (rhs, unit),
+                    _ => (rhs_unit.convert(rhs, lhs_unit).unwrap_or(rhs), Unit::None),
+                };
+                if rhs == 0.0 {
+                    return Err(Error::new_format("Division by zero"));
+                }
+                Ok((lhs / rhs, unit))
+            }
+        }
+    }
+}
+
+/// Convert `val` to `unit` so that it can be compared to a value of that unit
+fn same_unit(unit: Unit, val: f64, val_unit: Unit) -> Result<f64> {
+    if unit == Unit::None || val_unit == Unit::None {
+        Ok(val)
+    } else {
+        val_unit
+            .convert(val, unit)
+            .map_err(|_| combine_error(unit, val_unit))
+    }
+}
+
+/// Convert `val` to `unit` so that it can be added to or subtracted from a value of that unit
+fn summand(unit: Unit, val: f64, val_unit: Unit) -> Result<f64> {
+    // Temperature scales have different zero points, so converting one operand would offset the
+    // result (e.g. by 273.15 for kelvins)
+    if unit != val_unit && unit.is_temperature() && val_unit.is_temperature() {
+        return Err(combine_error(unit, val_unit));
+    }
+    same_unit(unit, val, val_unit)
+}
+
+fn combine_error(unit: Unit, val_unit: Unit) -> Error {
+    Error::new_format(format!(
+        "Cannot combine '{}' with '{}' in an expression",
+        val_unit, unit
+    ))
+}
+
+/// The unit of the result of combining two values, assuming one of them may be unitless
+fn unit_or(unit: Unit, other: Unit) -> Unit {
+    if unit == Unit::None {
+        other
+    } else {
+        unit
+    }
+}
+
+impl FromStr for Func {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "min" => Ok(Self::Min),
+            "max" => Ok(Self::Max),
+            "round" => Ok(Self::Round),
+            "floor" => Ok(Self::Floor),
+            "ceil" => Ok(Self::Ceil),
+            "abs" => Ok(Self::Abs),
+            x => Err(Error::new(format!("Unknown function '{}'", x))),
+        }
+    }
+}
+
+impl FromStr for Expr {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        let mut it = s.chars().peekable();
+        let expr = read_sum(&mut it)?;
+        skip_spaces(&mut it);
+        match it.next() {
+            None => Ok(expr),
+            Some(c) => Err(Error::new(format!(
+                "Unexpected '{}' in expression '{}'",
+                c, s
+            ))),
+        }
+    }
+}
+
+fn skip_spaces(it: &mut Peekable<Chars>) {
+    while it.next_if(|c| c.is_whitespace()).is_some() {}
+}
+
+fn read_sum(it: &mut Peekable<Chars>) -> Result<Expr> {
+    let mut expr = read_product(it)?;
+    loop {
+        skip_spaces(it);
+        let op = match it.peek() {
+            Some('+') => BinOp::Add,
+            Some('-') => BinOp::Sub,
+            _ => return Ok(expr),
+        };
+        let _ = it.next();
+        expr = Expr::BinOp(Box::new(expr), op, Box::new(read_product(it)?));
+    }
+}
+
+fn read_product(it: &mut Peekable<Chars>) -> Result<Expr> {
+    let mut expr = read_factor(it)?;
+    loop {
+        skip_spaces(it);
+        let op = match it.peek() {
+            Some('*') => BinOp::Mul,
+            Some('/') => BinOp::Div,
+            _ => return Ok(expr),
+        };
+        let _ = it.next();
+        expr = Expr::BinOp(Box::new(expr), op, Box::new(read_factor(it)?));
+    }
+}
+
+fn read_factor(it: &mut Peekable<Chars>) -> Result<Expr> {
+    skip_spaces(it);
+    match it.peek().copied() {
+        Some('-') => {
+            let _ = it.next();
+            Ok(Expr::Neg(Box::new(read_factor(it)?)))
+        }
+        Some('(') => {
+            let _ = it.next();
+            let expr = read_sum(it)?;
+            skip_spaces(it);
+            it.next_if_eq(&')').error("Missing ')' in expression")?;
+            Ok(expr)
+        }
+        Some('$') => {
+            let _ = it.next();
+            let name = read_name(it);
+            if name.is_empty() {
+                Err(Error::new("Missing placeholder name in expression"))
+            } else {
+                Ok(Expr::Var(name))
+            }
+        }
+        Some(c) if c.is_ascii_digit() || c == '.' => {
+            let mut number = String::new();
+            while let Some(c) = it.next_if(|c| c.is_ascii_digit() || *c == '.') {
+                number.push(c);
+            }
+            number
+                .parse()
+                .map(Expr::Number)
+                .or_error(|| format!("Invalid number '{}' in expression", number))
+        }
+        Some(c) if c.is_alphabetic() => {
+            let name = read_name(it);
+            let func: Func = name.parse()?;
+            skip_spaces(it);
+            it.next_if_eq(&'(')
+                .or_error(|| format!("Missing '(' after function '{}'", name))?;
+            let mut args = vec![read_sum(it)?];
+            loop {
+                skip_spaces(it);
+                match it.next() {
+                    Some(',') => args.push(read_sum(it)?),
+                    Some(')') => break,
+                    _ => return Err(Error::new("Missing ')' in expression")),
+                }
+            }
+            let arity_ok = match func {
+                Func::Min | Func::Max => args.len() >= 2,
+                _ => args.len() == 1,
+            };
+            if !arity_ok {
+                return Err(Error::new(format!(
+                    "Wrong number of arguments for function '{}'",
+                    name
+                )));
+            }
+            Ok(Expr::Func(func, args))
+        }
+        Some(c) => Err(Error::new(format!("Unexpected '{}' in expression", c))),
+        None => Err(Error::new("Unexpected end of expression")),
+    }
+}
+
+fn read_name(it: &mut Peekable<Chars>) -> String {
+    let mut name = String::new();
+    while let Some(c) = it.next_if(|c| c.is_alphabetic() || *c == '_') {
+        name.push(c);
+    }
+    name
+}
@@ -1,5 +1,8 @@
+use chrono::format::{Item, StrftimeItems};
 use smallvec::SmallVec;
 use smartstring::alias::String;
+use std::borrow::Cow;
+use std::collections::VecDeque;
 use std::fmt::Debug;
 use std::iter::repeat;
 use std::str::FromStr;
@@ -8,12 +11,15 @@ use std::time::{Duration, Instant};
 use tokio::sync::mpsc::Sender;
 
 use super::prefix::Prefix;
+use super::template::FormatTemplate;
 use super::unit::Unit;
 use super::value::Value;
-use super::Handles;
+use super::{item_values, Handles, History};
+use crate::config::SharedConfig;
 use crate::errors::*;
 use crate::escape::CollectEscaped;
-use crate::{Request, RequestCmd};
+use crate::util::format_vec_to_bar_graph;
+use crate::{wait_until_resumed, Request, RequestCmd};
 
 const DEFAULT_STR_MIN_WIDTH: usize = 0;
 const DEFAULT_STR_MAX_WIDTH: Option<usize> = None;
@@ -24,6 +30,16 @@ const DEFAULT_STRROT_INTERVAL: f64 = 1.0;
 const DEFAULT_BAR_WIDTH: usize = 5;
 const DEFAULT_BAR_MAX_VAL: f64 = 100.0;
 
+const DEFAULT_GRAPH_WIDTH: usize = 10;
+
+const DEFAULT_JOIN_SEPARATOR: &str = " ";
+
+const DEFAULT_ENG_WIDTH: usize = 3;
+const DEFAULT_FIX_PRECISION: usize = 1;
+
+const DEFAULT_DURATION_STYLE: DurationStyle = DurationStyle::Compact;
+const DEFAULT_DATETIME_FORMAT: &str = "%a %d/%m %R";
+
 pub const DEFAULT_STRING_FORMATTER: StrFormatter = StrFormatter {
     min_width: DEFAULT_STR_MIN_WIDTH,
     max_width: DEFAULT_STR_MAX_WIDTH,
@@ -44,6 +60,18 @@ pub const DEFAULT_NUMBER_FORMATTER: EngFormatter = EngFormatter(EngFixConfig {
     },
 });
 
+pub const DEFAULT_DURATION_FORMATTER: DurationFormatter = DurationFormatter {
+    style: DEFAULT_DURATION_STYLE,
+};
+
+pub static DEFAULT_DATETIME_FORMATTER: DatetimeFormatter = DatetimeFormatter {
+    format: Cow::Borrowed(DEFAULT_DATETIME_FORMAT),
+};
+
+pub static DEFAULT_LIST_FORMATTER: JoinFormatter = JoinFormatter {
+    separator: Cow::Borrowed(DEFAULT_JOIN_SEPARATOR),
+};
+
 pub const DEFAULT_FLAG_FORMATTER: FlagFormatter = FlagFormatter;
 
 enum StrArgs {
@@ -67,12 +95,75 @@ enum EngFixArgs {
     Prefix,
 }
 
+enum GraphArgs {
+    Width,
+}
+
+enum JoinArgs {
+    Separator,
+}
+
+enum AtArgs {
+    Index,
+}
+
+enum DurationArgs {
+    Style,
+}
+
+enum DatetimeArgs {
+    Format,
+}
+
 pub trait Formatter: Debug {
-    fn format(&self, val: &Value) -> Result<String>;
+    fn format(&self, val: &Value, config: &SharedConfig) -> Result<String>;
+
+    /// The number of previous values of the placeholder this formatter needs
+    fn history_len(&self) -> usize {
+        0
+    }
+
+    /// Format a value knowing its previous values (including the current one)
+    fn format_history(
+        &self,
+        val: &Value,
+        _history: &VecDeque<f64>,
+        config: &SharedConfig,
+    ) -> Result<String> {
+        self.format(val, config)
+    }
+
+    /// The history of a single item, if this formatter renders a template for every item of a
+    /// list which keeps previous values
+    fn item_history(&self) -> Option<History> {
+        None
+    }
+
+    /// Format a list knowing the histories of its items, by their index
+    fn format_items(
+        &self,
+        val: &Value,
+        _items: &[History],
+        config: &SharedConfig,
+    ) -> Result<String> {
+        self.format(val, config)
+    }
 
     fn init(&self, _tx: &Sender<Request>, _block_id: usize, _handles: &mut Handles) {}
 }
 
+/// The formatter used for placeholders without an explicitly set one
+pub fn default_formatter(val: &Value) -> &'static (dyn Formatter + Send + Sync) {
+    match val {
+        Value::Text(_) | Value::Icon(_) => &DEFAULT_STRING_FORMATTER,
+        Value::Number { .. } => &DEFAULT_NUMBER_FORMATTER,
+        Value::Duration(_) => &DEFAULT_DURATION_FORMATTER,
+        Value::Timestamp(_) => &DEFAULT_DATETIME_FORMATTER,
+        Value::List(_) => &DEFAULT_LIST_FORMATTER,
+        Value::Flag => &DEFAULT_FLAG_FORMATTER,
+    }
+}
+
 pub fn new_formatter(name: &str, args: &[String]) -> Result<Box<dyn Formatter + Send + Sync>> {
     match name {
         "str" => {
@@ -127,8 +218,63 @@ pub fn new_formatter(name: &str, args: &[String]) -> Result<Box<dyn Formatter +
             };
             Ok(Box::new(BarFormatter { width, max_value }))
         }
-        "eng" => Ok(Box::new(EngFormatter(EngFixConfig::from_args(args)?))),
-        "fix" => Ok(Box::new(FixFormatter(EngFixConfig::from_args(args)?))),
+        "graph" => {
+            let width: usize = match args.get(GraphArgs::Width as usize) {
+                Some(v) => v.parse().error("Width must be a positive integer")?,
+                None => DEFAULT_GRAPH_WIDTH,
+            };
+            if width == 0 {
+                return Err(Error::new("Width must be a positive integer"));
+            }
+            Ok(Box::new(GraphFormatter { width }))
+        }
+        "eng" => Ok(Box::new(EngFormatter(EngFixConfig::from_args(
+            args,
+            DEFAULT_ENG_WIDTH,
+        )?))),
+        "fix" => {
+            let config = EngFixConfig::from_args(args, DEFAULT_FIX_PRECISION)?;
+            if let Some((_, true)) = config.prefix.prefix {
+                return Err(Error::new(
+                    "'fix' always uses the given prefix, it cannot be forced with '!'",
+                ));
+            }
+            Ok(Box::new(FixFormatter(config)))
+        }
+        "join" => {
+            let separator: Cow<'static, str> = match args.get(JoinArgs::Separator as usize) {
+                Some(v) => Cow::Owned(v.to_string()),
+                None => Cow::Borrowed(DEFAULT_JOIN_SEPARATOR),
+            };
+            Ok(Box::new(JoinFormatter { separator }))
+        }
+        "count" => Ok(Box::new(CountFormatter)),
+        "first" => Ok(Box::new(AtFormatter { index: 0 })),
+        "at" => {
+            let index: usize = args
+                .get(AtArgs::Index as usize)
+                .error("'at' formatter requires an index")?
+                .parse()
+                .error("Index must be a positive integer")?;
+            Ok(Box::new(AtFormatter { index }))
+        }
+        "dur" => {
+            let style: DurationStyle = match args.get(DurationArgs::Style as usize) {
+                Some(v) => v.parse()?,
+                None => DEFAULT_DURATION_STYLE,
+            };
+            Ok(Box::new(DurationFormatter { style }))
+        }
+        "datetime" => {
+            let format: Cow<'static, str> = match args.get(DatetimeArgs::Format as usize) {
+                Some(v) => Cow::Owned(v.to_string()),
+                None => Cow::Borrowed(DEFAULT_DATETIME_FORMAT),
+            };
+            if StrftimeItems::new(&format).any(|item| matches!(item, Item::Error)) {
+                return Err(Error::new(format!("Invalid datetime format: '{}'", format)));
+            }
+            Ok(Box::new(DatetimeFormatter { format }))
+        }
         _ => Err(Error::new(format!("Unknown formatter: '{}'", name))),
     }
 }
@@ -140,7 +286,7 @@ pub struct StrFormatter {
 }
 
 impl Formatter for StrFormatter {
-    fn format(&self, val: &Value) -> Result<String> {
+    fn format(&self, val: &Value, _config: &SharedConfig) -> Result<String> {
         match val {
             Value::Text(text) => {
                 let width = text.chars().count();
@@ -157,6 +303,15 @@ impl Formatter for StrFormatter {
             Value::Flag => Err(Error::new_format(
                 "A flag cannot be formatted with 'str' formatter",
             )),
+            Value::List(_) => Err(Error::new_format(
+                "A list cannot be formatted with 'str' formatter",
+            )),
+            Value::Duration(_) => Err(Error::new_format(
+                "A duration cannot be formatted with 'str' formatter",
+            )),
+            Value::Timestamp(_) => Err(Error::new_format(
+                "A timestamp cannot be formatted with 'str' formatter",
+            )),
         }
     }
 }
@@ -169,7 +324,7 @@ pub struct RotStrFormatter {
 }
 
 impl Formatter for RotStrFormatter {
-    fn format(&self, val: &Value) -> Result<String> {
+    fn format(&self, val: &Value, _config: &SharedConfig) -> Result<String> {
         match val {
             Value::Text(text) => {
                 let full_width = text.chars().count();
@@ -203,6 +358,15 @@ impl Formatter for RotStrFormatter {
             Value::Flag => Err(Error::new_format(
                 "A flag cannot be formatted with 'rot-str' formatter",
             )),
+            Value::List(_) => Err(Error::new_format(
+                "A list cannot be formatted with 'rot-str' formatter",
+            )),
+            Value::Duration(_) => Err(Error::new_format(
+                "A duration cannot be formatted with 'rot-str' formatter",
+            )),
+            Value::Timestamp(_) => Err(Error::new_format(
+                "A timestamp cannot be formatted with 'rot-str' formatter",
+            )),
         }
     }
 
@@ -218,6 +382,8 @@ impl Formatter for RotStrFormatter {
                 cmds.push(RequestCmd::Noop);
                 tx.send(Request { block_id, cmds }).await.unwrap();
                 interval.tick().await;
+                // Don't rotate the text while nobody sees it
+                wait_until_resumed().await;
             }
         }));
     }
@@ -235,7 +401,7 @@ const VERTICAL_BAR_CHARS: [char; 9] = [
 ];
 
 impl Formatter for BarFormatter {
-    fn format(&self, val: &Value) -> Result<String> {
+    fn format(&self, val: &Value, _config: &SharedConfig) -> Result<String> {
         match val {
             Value::Number { mut val, .. } => {
                 val = (val / self.max_value).clamp(0., 1.);
@@ -255,6 +421,72 @@ impl Formatter for BarFormatter {
             Value::Flag => Err(Error::new_format(
                 "A flag cannot be formatted with 'bar' formatter",
             )),
+            Value::List(_) => Err(Error::new_format(
+                "A list cannot be formatted with 'bar' formatter",
+            )),
+            Value::Duration(_) => Err(Error::new_format(
+                "A duration cannot be formatted with 'bar' formatter",
+            )),
+            Value::Timestamp(_) => Err(Error::new_format(
+                "A timestamp cannot be formatted with 'bar' formatter",
+            )),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct GraphFormatter {
+    width: usize,
+}
+
+impl Formatter for GraphFormatter {
+    fn format(&self, val: &Value, config: &SharedConfig) -> Result<String> {
+        let history = match val {
+            Value::Number { val, .. } => VecDeque::from([*val]),
+            _ => VecDeque::new(),
+        };
+        self.format_history(val, &history, config)
+    }
+
+    fn history_len(&self) -> usize {
+        self.width
+    }
+
+    fn format_history(
+        &self,
+        val: &Value,
+        history: &VecDeque<f64>,
+        _config: &SharedConfig,
+    ) -> Result<String> {
+        match val {
+            Value::Number { .. } => {
+                let values: Vec<f64> = history
+                    .iter()
+                    .skip(history.len().saturating_sub(self.width))
+                    .copied()
+                    .collect();
+                let mut retval: String = repeat(' ').take(self.width - values.len()).collect();
+                retval.push_str(&format_vec_to_bar_graph(&values));
+                Ok(retval)
+            }
+            Value::Text(_) => Err(Error::new_format(
+                "Text cannot be formatted with 'graph' formatter",
+            )),
+            Value::Icon(_) => Err(Error::new_format(
+                "An icon cannot be formatted with 'graph' formatter",
+            )),
+            Value::Duration(_) => Err(Error::new_format(
+                "A duration cannot be formatted with 'graph' formatter",
+            )),
+            Value::Timestamp(_) => Err(Error::new_format(
+                "A timestamp cannot be formatted with 'graph' formatter",
+            )),
+            Value::Flag => Err(Error::new_format(
+                "A flag cannot be formatted with 'graph' formatter",
+            )),
+            Value::List(_) => Err(Error::new_format(
+                "A list cannot be formatted with 'graph' formatter",
+            )),
         }
     }
 }
@@ -340,10 +572,10 @@ struct EngFixConfig {
 }
 
 impl EngFixConfig {
-    fn from_args(args: &[String]) -> Result<Self> {
+    fn from_args(args: &[String], default_width: usize) -> Result<Self> {
         let width: usize = match args.get(EngFixArgs::Width as usize) {
             Some(v) => v.parse().error("Width must be a positive integer")?,
-            None => 3,
+            None => default_width,
         };
         let unit: UnitConfig = match args.get(EngFixArgs::Unit as usize).map(|x| x.as_str()) {
             Some("auto") | None => Default::default(),
@@ -359,13 +591,29 @@ impl EngFixConfig {
             prefix,
         })
     }
+
+    /// Append prefix and unit to the formatted number, unless they are hidden
+    fn push_suffix(&self, retval: &mut String, prefix: Prefix, unit: Unit) {
+        if !self.prefix.hidden {
+            if self.prefix.has_space {
+                retval.push(' ');
+            }
+            retval.push_str(&prefix.to_string());
+        }
+        if !self.unit.hidden {
+            if self.unit.has_space {
+                retval.push(' ');
+            }
+            retval.push_str(&unit.to_string());
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct EngFormatter(EngFixConfig);
 
 impl Formatter for EngFormatter {
-    fn format(&self, val: &Value) -> Result<String> {
+    fn format(&self, val: &Value, config: &SharedConfig) -> Result<String> {
         match val {
             Value::Number {
                 mut val,
@@ -383,10 +631,16 @@ impl Formatter for EngFormatter {
                     None => (Prefix::min_available(), Prefix::max_available()),
                 };
 
-                let prefix = unit.clamp_prefix(
-                    Prefix::from_exp_level(val.log10().div_euclid(3.) as i32)
-                        .clamp(min_prefix, max_prefix),
-                );
+                let binary = unit.allows_binary_prefix()
+                    && match self.0.prefix.prefix {
+                        Some((prefix, _)) => prefix.is_binary(),
+                        None => config.binary_prefixes,
+                    };
+                let mut prefix = Prefix::fitting(val, binary).clamp(min_prefix, max_prefix);
+                if binary {
+                    prefix = prefix.binary();
+                }
+                let prefix = unit.clamp_prefix(prefix);
                 val = prefix.apply(val);
 
                 let mut digits = (val.max(1.).log10().floor() + 1.0) as isize;
@@ -400,18 +654,7 @@ impl Formatter for EngFormatter {
                     1 => format!(" {}", val.floor() as i64),
                     rest => format!("{:.*}", rest as usize - 1, val),
                 });
-                if !self.0.prefix.hidden {
-                    if self.0.prefix.has_space {
-                        retval.push(' ');
-                    }
-                    retval.push_str(&prefix.to_string());
-                }
-                if !self.0.unit.hidden {
-                    if self.0.unit.has_space {
-                        retval.push(' ');
-                    }
-                    retval.push_str(&unit.to_string());
-                }
+                self.0.push_suffix(&mut retval, prefix, unit);
 
                 Ok(retval)
             }
@@ -424,6 +667,15 @@ impl Formatter for EngFormatter {
             Value::Flag => Err(Error::new_format(
                 "A flag cannot be formatted with 'eng' formatter",
             )),
+            Value::List(_) => Err(Error::new_format(
+                "A list cannot be formatted with 'eng' formatter",
+            )),
+            Value::Duration(_) => Err(Error::new_format(
+                "A duration cannot be formatted with 'eng' formatter",
+            )),
+            Value::Timestamp(_) => Err(Error::new_format(
+                "A timestamp cannot be formatted with 'eng' formatter",
+            )),
         }
     }
 }
@@ -432,14 +684,31 @@ impl Formatter for EngFormatter {
 pub struct FixFormatter(EngFixConfig);
 
 impl Formatter for FixFormatter {
-    fn format(&self, val: &Value) -> Result<String> {
+    fn format(&self, val: &Value, _config: &SharedConfig) -> Result<String> {
         match val {
             Value::Number {
-                ..
-                // mut val,
-                // unit,
-                // icon,
-            } => Err(Error::new_format("'fix' formatter is not implemented yet")),
+                mut val,
+                mut unit,
+                icon,
+            } => {
+                if let Some(new_unit) = self.0.unit.unit {
+                    val = unit.convert(val, new_unit)?;
+                    unit = new_unit;
+                }
+
+                // Unlike 'eng', the prefix is never chosen automatically
+                let prefix = unit.clamp_prefix(match self.0.prefix.prefix {
+                    Some((prefix, _)) => prefix,
+                    None => Prefix::One,
+                });
+                val = prefix.apply(val);
+
+                let mut retval = icon.clone();
+                retval.push_str(&format!("{:.*}", self.0.width, val));
+                self.0.push_suffix(&mut retval, prefix, unit);
+
+                Ok(retval)
+            }
             Value::Text(_) => Err(Error::new_format(
                 "Text cannot be formatted with 'fix' formatter",
             )),
@@ -449,18 +718,407 @@ impl Formatter for FixFormatter {
             Value::Flag => Err(Error::new_format(
                 "A flag cannot be formatted with 'fix' formatter",
             )),
+            Value::List(_) => Err(Error::new_format(
+                "A list cannot be formatted with 'fix' formatter",
+            )),
+            Value::Duration(_) => Err(Error::new_format(
+                "A duration cannot be formatted with 'fix' formatter",
+            )),
+            Value::Timestamp(_) => Err(Error::new_format(
+                "A timestamp cannot be formatted with 'fix' formatter",
+            )),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum DurationStyle {
+    /// `1:02:03`
+    Hms,
+    /// `1:02`
+    Hm,
+    /// `63 min`, rounded up
+    Min,
+    /// Two biggest units: `1w 2d`, `1d 2h`, `1h 2m` or `1m 2s`
+    Compact,
+}
+
+impl FromStr for DurationStyle {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s {
+            "hms" => Ok(Self::Hms),
+            "hm" => Ok(Self::Hm),
+            "min" => Ok(Self::Min),
+            "compact" => Ok(Self::Compact),
+            x => Err(Error::new(format!("Unknown duration style: '{}'", x))),
         }
     }
 }
 
+#[derive(Debug)]
+pub struct DurationFormatter {
+    style: DurationStyle,
+}
+
+impl Formatter for DurationFormatter {
+    fn format(&self, val: &Value, _config: &SharedConfig) -> Result<String> {
+        match val {
+            Value::Duration(dur) => {
+                let secs = dur.as_secs();
+                let (hours, minutes, seconds) = (secs / 3_600, secs % 3_600 / 60, secs % 60);
+                Ok(match self.style {
+                    DurationStyle::Hms => format!("{}:{:02}:{:02}", hours, minutes, seconds),
+                    DurationStyle::Hm => format!("{}:{:02}", hours, minutes),
+                    DurationStyle::Min => format!("{} min", (secs + 59) / 60),
+                    DurationStyle::Compact => {
+                        let (weeks, days, hours) =
+                            (secs / 604_800, secs % 604_800 / 86_400, hours % 24);
+                        if weeks > 0 {
+                            format!("{}w {}d", weeks, days)
+                        } else if days > 0 {
+                            format!("{}d {}h", days, hours)
+                        } else if hours > 0 {
+                            format!("{}h {}m", hours, minutes)
+                        } else {
+                            format!("{}m {}s", minutes, seconds)
+                        }
+                    }
+                }
+                .into())
+            }
+            Value::Text(_) => Err(Error::new_format(
+                "Text cannot be formatted with 'dur' formatter",
+            )),
+            Value::Icon(_) => Err(Error::new_format(
+                "An icon cannot be formatted with 'dur' formatter",
+            )),
+            Value::Number { .. } => Err(Error::new_format(
+                "A number cannot be formatted with 'dur' formatter",
+            )),
+            Value::Timestamp(_) => Err(Error::new_format(
+                "A timestamp cannot be formatted with 'dur' formatter",
+            )),
+            Value::Flag => Err(Error::new_format(
+                "A flag cannot be formatted with 'dur' formatter",
+            )),
+            Value::List(_) => Err(Error::new_format(
+                "A list cannot be formatted with 'dur' formatter",
+            )),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct DatetimeFormatter {
+    format: Cow<'static, str>,
+}
+
+impl Formatter for DatetimeFormatter {
+    fn format(&self, val: &Value, _config: &SharedConfig) -> Result<String> {
+        match val {
+            Value::Timestamp(ts) => Ok(ts.format(&self.format).to_string().chars().collect_pango()),
+            Value::Text(_) => Err(Error::new_format(
+                "Text cannot be formatted with 'datetime' formatter",
+            )),
+            Value::Icon(_) => Err(Error::new_format(
+                "An icon cannot be formatted with 'datetime' formatter",
+            )),
+            Value::Number { .. } => Err(Error::new_format(
+                "A number cannot be formatted with 'datetime' formatter",
+            )),
+            Value::Duration(_) => Err(Error::new_format(
+                "A duration cannot be formatted with 'datetime' formatter",
+            )),
+            Value::Flag => Err(Error::new_format(
+                "A flag cannot be formatted with 'datetime' formatter",
+            )),
+            Value::List(_) => Err(Error::new_format(
+                "A list cannot be formatted with 'datetime' formatter",
+            )),
+        }
+    }
+}
+
+fn list_error(val: &Value, formatter: &str) -> Error {
+    Error::new_format(match val {
+        Value::Text(_) => format!("Text cannot be formatted with '{}' formatter", formatter),
+        Value::Icon(_) => format!("An icon cannot be formatted with '{}' formatter", formatter),
+        Value::Number { .. } => {
+            format!(
+                "A number cannot be formatted with '{}' formatter",
+                formatter
+            )
+        }
+        Value::Duration(_) => format!(
+            "A duration cannot be formatted with '{}' formatter",
+            formatter
+        ),
+        Value::Timestamp(_) => format!(
+            "A timestamp cannot be formatted with '{}' formatter",
+            formatter
+        ),
+        Value::Flag => format!("A flag cannot be formatted with '{}' formatter", formatter),
+        Value::List(_) => unreachable!(),
+    })
+}
+
+#[derive(Debug)]
+pub struct JoinFormatter {
+    separator: Cow<'static, str>,
+}
+
+impl Formatter for JoinFormatter {
+    fn format(&self, val: &Value, config: &SharedConfig) -> Result<String> {
+        match val {
+            Value::List(items) => {
+                let mut retval = String::new();
+                for (i, item) in items.iter().enumerate() {
+                    if i != 0 {
+                        retval.push_str(&self.separator);
+                    }
+                    retval.push_str(&default_formatter(item).format(item, config)?);
+                }
+                Ok(retval)
+            }
+            other => Err(list_error(other, "join")),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct CountFormatter;
+
+impl Formatter for CountFormatter {
+    fn format(&self, val: &Value, _config: &SharedConfig) -> Result<String> {
+        match val {
+            Value::List(items) => Ok(items.len().to_string().into()),
+            other => Err(list_error(other, "count")),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct AtFormatter {
+    index: usize,
+}
+
+impl Formatter for AtFormatter {
+    fn format(&self, val: &Value, config: &SharedConfig) -> Result<String> {
+        match val {
+            Value::List(items) => {
+                let item = items
+                    .get(self.index)
+                    .format_error(format!("The list has no item at index {}", self.index))?;
+                default_formatter(item).format(item, config)
+            }
+            other => Err(list_error(other, "at")),
+        }
+    }
+}
+
+/// Renders a template for every item of a list. The item is available as `$item` and its index
+/// as `$index`.
+#[derive(Debug)]
+pub struct EachFormatter {
+    template: FormatTemplate,
+    separator: String,
+}
+
+impl EachFormatter {
+    pub fn new(template: FormatTemplate, separator: String) -> Self {
+        Self {
+            template,
+            separator,
+        }
+    }
+}
+
+impl Formatter for EachFormatter {
+    fn format(&self, val: &Value, config: &SharedConfig) -> Result<String> {
+        self.format_items(val, &[], config)
+    }
+
+    fn item_history(&self) -> Option<History> {
+        let mut history = History::default();
+        self.template.track_history(&mut history);
+        if history.is_empty() {
+            None
+        } else {
+            Some(history)
+        }
+    }
+
+    fn format_items(
+        &self,
+        val: &Value,
+        items: &[History],
+        config: &SharedConfig,
+    ) -> Result<String> {
+        match val {
+            Value::List(list) => {
+                let empty = History::default();
+                let mut retval = String::new();
+                for (i, item) in list.iter().enumerate() {
+                    if i != 0 {
+                        retval.push_str(&self.separator);
+                    }
+                    let vars = item_values(item, i);
+                    let history = items.get(i).unwrap_or(&empty);
+                    retval.push_str(&self.template.render(&vars, history, config)?);
+                }
+                Ok(retval)
+            }
+            other => Err(list_error(other, "each")),
+        }
+    }
+
+    fn init(&self, tx: &Sender<Request>, block_id: usize, handles: &mut Handles) {
+        self.template.init(tx, block_id, handles);
+    }
+}
+
 #[derive(Debug)]
 pub struct FlagFormatter;
 
 impl Formatter for FlagFormatter {
-    fn format(&self, val: &Value) -> Result<String> {
+    fn format(&self, val: &Value, _config: &SharedConfig) -> Result<String> {
         match val {
-            Value::Number { .. } | Value::Text(_) | Value::Icon(_) => unreachable!(),
+            Value::Number { .. }
+            | Value::Text(_)
+            | Value::Icon(_)
+            | Value::Duration(_)
+            | Value::Timestamp(_)
+            | Value::List(_) => unreachable!(),
             Value::Flag => Ok(String::new()),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, TimeZone};
+
+    fn format(formatter: &dyn Formatter, val: Value) -> Result<String> {
+        formatter.format(&val, &SharedConfig::default())
+    }
+
+    fn new(name: &str, args: &[&str]) -> Result<Box<dyn Formatter + Send + Sync>> {
+        let args: Vec<String> = args.iter().map(|&arg| arg.into()).collect();
+        new_formatter(name, &args)
+    }
+
+    #[test]
+    fn duration() {
+        let secs = |secs| Value::duration(Duration::from_secs(secs));
+        let dur = |style| DurationFormatter { style };
+
+        let hms = dur(DurationStyle::Hms);
+        assert_eq!(format(&hms, secs(3_723)).unwrap(), "1:02:03");
+        assert_eq!(format(&hms, secs(100 * 3_600)).unwrap(), "100:00:00");
+        assert_eq!(
+            format(&dur(DurationStyle::Hm), secs(3_723)).unwrap(),
+            "1:02"
+        );
+
+        let min = dur(DurationStyle::Min);
+        assert_eq!(format(&min, secs(0)).unwrap(), "0 min");
+        assert_eq!(format(&min, secs(61)).unwrap(), "2 min");
+        assert_eq!(format(&min, secs(3_723)).unwrap(), "63 min");
+
+        let compact = dur(DurationStyle::Compact);
+        assert_eq!(format(&compact, secs(59)).unwrap(), "0m 59s");
+        assert_eq!(format(&compact, secs(3_723)).unwrap(), "1h 2m");
+        assert_eq!(format(&compact, secs(90_061)).unwrap(), "1d 1h");
+        assert_eq!(format(&compact, secs(9 * 86_400)).unwrap(), "1w 2d");
+
+        assert!(format(&compact, Value::number(1)).is_err());
+        assert_eq!(
+            new("dur", &[])
+                .unwrap()
+                .format(&secs(59), &SharedConfig::default())
+                .unwrap(),
+            "0m 59s"
+        );
+        assert_eq!(
+            new("dur", &["hms"])
+                .unwrap()
+                .format(&secs(59), &SharedConfig::default())
+                .unwrap(),
+            "0:00:59"
+        );
+        assert!(new("dur", &["days"]).is_err());
+    }
+
+    #[test]
+    fn graph() {
+        let config = SharedConfig::default();
+        let graph = GraphFormatter { width: 4 };
+        let history = |values: &[f64]| values.iter().copied().collect::<VecDeque<f64>>();
+
+        assert_eq!(
+            graph
+                .format_history(&Value::number(3), &history(&[1., 2., 3.]), &config)
+                .unwrap(),
+            " ▁▄█"
+        );
+        assert_eq!(
+            graph
+                .format_history(&Value::number(4), &history(&[0., 1., 2., 3., 4.]), &config)
+                .unwrap(),
+            "▁▃▅█"
+        );
+        assert_eq!(format(&graph, Value::number(5)).unwrap(), "   ▁");
+        assert!(format(&graph, Value::text("a".into())).is_err());
+
+        assert_eq!(new("graph", &[]).unwrap().history_len(), 10);
+        assert_eq!(new("graph", &["5"]).unwrap().history_len(), 5);
+        assert_eq!(new("eng", &[]).unwrap().history_len(), 0);
+    }
+
+    #[test]
+    fn datetime() {
+        let ts = Value::timestamp(Local.ymd(2022, 3, 4).and_hms(5, 6, 7));
+        let datetime = |format: &'static str| DatetimeFormatter {
+            format: Cow::Borrowed(format),
+        };
+
+        assert_eq!(
+            format(&datetime("%F %T"), ts.clone()).unwrap(),
+            "2022-03-04 05:06:07"
+        );
+        assert_eq!(
+            format(&DEFAULT_DATETIME_FORMATTER, ts.clone()).unwrap(),
+            "Fri 04/03 05:06"
+        );
+        // The result is escaped
+        assert_eq!(format(&datetime("<%H>"), ts.clone()).unwrap(), "&lt;05&gt;");
+
+        assert!(format(&datetime("%F"), Value::duration(Duration::from_secs(1))).is_err());
+        assert!(new("datetime", &["%R"]).is_ok());
+        assert!(new("datetime", &["%Q"]).is_err());
+    }
+
+    #[test]
+    fn fix() {
+        let fix = |args: &[&str], val: Value| format(&*new("fix", args).unwrap(), val).unwrap();
+
+        assert_eq!(fix(&[], Value::number(1.26)), "1.3");
+        assert_eq!(fix(&["2"], Value::percents(42)), "42.00%");
+        // The prefix is never chosen automatically
+        assert_eq!(fix(&["0"], Value::bytes(1500)), "1500B");
+        assert_eq!(fix(&["1", "B", "K"], Value::bytes(1500)), "1.5KB");
+        assert_eq!(fix(&["1", "B", "Ki"], Value::bytes(1536)), "1.5KiB");
+        assert_eq!(fix(&["0", "b", " M"], Value::bytes(1_000_000)), "8 Mb");
+        assert_eq!(fix(&["1", " _B", "_K"], Value::bytes(1500)), "1.5");
+        // Units which do not allow the prefix ignore it
+        assert_eq!(fix(&["1", "auto", "K"], Value::percents(50)), "50.0%");
+        assert_eq!(fix(&["1", "auto", "m"], Value::bytes(2)), "2.0B");
+
+        assert!(new("fix", &["1", "B", "!K"]).is_err());
+        assert!(new("fix", &["x"]).is_err());
+        assert!(format(&*new("fix", &[]).unwrap(), Value::text("1".into())).is_err());
+    }
+}
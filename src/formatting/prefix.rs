@@ -1,28 +1,37 @@
+use std::cmp::Ordering;
 use std::fmt;
 use std::ops::AddAssign;
 use std::str::FromStr;
 
 use crate::errors::*;
 
-/// SI prefix
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+/// SI or binary (IEC) prefix
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Prefix {
     /// `n`
-    Nano = -3,
+    Nano,
     /// `u`
-    Micro = -2,
+    Micro,
     /// `m`
-    Milli = -1,
+    Milli,
     /// `1`
-    One = 0,
+    One,
     /// `K`
-    Kilo = 1,
+    Kilo,
     /// `M`
-    Mega = 2,
+    Mega,
     /// `G`
-    Giga = 3,
+    Giga,
     /// `T`
-    Tera = 4,
+    Tera,
+    /// `Ki`
+    Kibi,
+    /// `Mi`
+    Mebi,
+    /// `Gi`
+    Gibi,
+    /// `Ti`
+    Tebi,
 }
 
 impl Prefix {
@@ -52,8 +61,43 @@ impl Prefix {
         }
     }
 
+    /// The power of the base (1000 or 1024) this prefix stands for
+    pub fn exp_level(self) -> i32 {
+        match self {
+            Self::Nano => -3,
+            Self::Micro => -2,
+            Self::Milli => -1,
+            Self::One => 0,
+            Self::Kilo | Self::Kibi => 1,
+            Self::Mega | Self::Mebi => 2,
+            Self::Giga | Self::Gibi => 3,
+            Self::Tera | Self::Tebi => 4,
+        }
+    }
+
+    pub fn is_binary(self) -> bool {
+        matches!(self, Self::Kibi | Self::Mebi | Self::Gibi | Self::Tebi)
+    }
+
+    /// The binary counterpart of this prefix. There are no binary prefixes below one.
+    pub fn binary(self) -> Self {
+        match self {
+            Self::Nano | Self::Micro | Self::Milli | Self::One => Self::One,
+            Self::Kilo | Self::Kibi => Self::Kibi,
+            Self::Mega | Self::Mebi => Self::Mebi,
+            Self::Giga | Self::Gibi => Self::Gibi,
+            Self::Tera | Self::Tebi => Self::Tebi,
+        }
+    }
+
+    /// The SI counterpart of this prefix
+    pub fn si(self) -> Self {
+        Self::from_exp_level(self.exp_level())
+    }
+
     pub fn apply(self, value: f64) -> f64 {
-        value / 1_000f64.powi(self as i32)
+        let base: f64 = if self.is_binary() { 1024. } else { 1000. };
+        value / base.powi(self.exp_level())
     }
 
     pub fn from_exp_level(exp_level: i32) -> Self {
@@ -68,11 +112,37 @@ impl Prefix {
             4..=i32::MAX => Prefix::Tera,
         }
     }
+
+    /// The largest prefix which keeps the integer part of `value` non-zero
+    pub fn fitting(value: f64, binary: bool) -> Self {
+        if binary {
+            Self::from_exp_level((value.log2() / 10.).floor() as i32).binary()
+        } else {
+            Self::from_exp_level(value.log10().div_euclid(3.) as i32)
+        }
+    }
+}
+
+impl PartialOrd for Prefix {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Prefix {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.exp_level(), self.is_binary()).cmp(&(other.exp_level(), other.is_binary()))
+    }
 }
 
 impl AddAssign<i32> for Prefix {
     fn add_assign(&mut self, rhs: i32) {
-        *self = Self::from_exp_level(*self as i32 + rhs);
+        let prefix = Self::from_exp_level(self.exp_level() + rhs);
+        *self = if self.is_binary() {
+            prefix.binary()
+        } else {
+            prefix
+        };
     }
 }
 
@@ -87,6 +157,10 @@ impl fmt::Display for Prefix {
             Self::Mega => "M",
             Self::Giga => "G",
             Self::Tera => "T",
+            Self::Kibi => "Ki",
+            Self::Mebi => "Mi",
+            Self::Gibi => "Gi",
+            Self::Tebi => "Ti",
         })
     }
 }
@@ -104,7 +178,51 @@ impl FromStr for Prefix {
             "M" => Ok(Prefix::Mega),
             "G" => Ok(Prefix::Giga),
             "T" => Ok(Prefix::Tera),
+            "Ki" => Ok(Prefix::Kibi),
+            "Mi" => Ok(Prefix::Mebi),
+            "Gi" => Ok(Prefix::Gibi),
+            "Ti" => Ok(Prefix::Tebi),
             x => Err(Error::new(format!("Unknown prefix: '{}'", x))),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fitting() {
+        assert_eq!(Prefix::fitting(0.5, false), Prefix::Milli);
+        assert_eq!(Prefix::fitting(1., false), Prefix::One);
+        assert_eq!(Prefix::fitting(999., false), Prefix::One);
+        assert_eq!(Prefix::fitting(1000., false), Prefix::Kilo);
+        assert_eq!(Prefix::fitting(2.5e9, false), Prefix::Giga);
+        assert_eq!(Prefix::fitting(1e18, false), Prefix::Tera);
+        assert_eq!(Prefix::fitting(1000., true), Prefix::One);
+        assert_eq!(Prefix::fitting(1024., true), Prefix::Kibi);
+        assert_eq!(Prefix::fitting(3. * 1024. * 1024., true), Prefix::Mebi);
+        assert_eq!(Prefix::fitting(0.5, true), Prefix::One);
+    }
+
+    #[test]
+    fn binary() {
+        assert_eq!(Prefix::Milli.binary(), Prefix::One);
+        assert_eq!(Prefix::One.binary(), Prefix::One);
+        assert_eq!(Prefix::Kilo.binary(), Prefix::Kibi);
+        assert_eq!(Prefix::Gibi.binary(), Prefix::Gibi);
+        assert_eq!(Prefix::Tebi.si(), Prefix::Tera);
+        assert_eq!(Prefix::Kibi.apply(2048.), 2.);
+        assert_eq!(Prefix::Kilo.apply(2048.), 2.048);
+    }
+
+    #[test]
+    fn ord() {
+        assert!(Prefix::Nano < Prefix::Milli);
+        assert!(Prefix::One < Prefix::Kilo);
+        assert!(Prefix::Kilo < Prefix::Kibi);
+        assert!(Prefix::Kibi < Prefix::Mega);
+        assert_eq!(Prefix::Kilo.max(Prefix::Kibi), Prefix::Kibi);
+        assert_eq!(Prefix::Giga.clamp(Prefix::One, Prefix::Mega), Prefix::Mega);
+    }
+}
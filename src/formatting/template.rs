@@ -1,8 +1,8 @@
-use super::formatter::{
-    new_formatter, Formatter, DEFAULT_FLAG_FORMATTER, DEFAULT_NUMBER_FORMATTER,
-    DEFAULT_STRING_FORMATTER,
-};
+use super::expr::Expr;
+use super::formatter::{default_formatter, new_formatter, EachFormatter, Formatter};
 use super::value::Value;
+use super::History;
+use crate::config::SharedConfig;
 use crate::errors::*;
 use crate::Request;
 
@@ -11,8 +11,10 @@ use tokio::sync::mpsc::Sender;
 use smartstring::alias::String;
 
 use std::collections::HashMap;
-use std::iter::Peekable;
-use std::str::FromStr;
+use std::fmt;
+use std::iter::{Chain, Once, Peekable};
+use std::str::{CharIndices, FromStr};
+use std::sync::Arc;
 
 #[derive(Debug)]
 pub struct FormatTemplate(pub Vec<TokenList>);
@@ -28,6 +30,31 @@ pub enum Token {
         name: String,
         formatter: Option<Box<dyn Formatter + Send + Sync>>,
     },
+    /// An arithmetic expression like `$(a + $b)`
+    Expr {
+        expr: Expr,
+        formatter: Option<Box<dyn Formatter + Send + Sync>>,
+    },
+    Condition(Condition),
+}
+
+/// A comparison like `$percentage < 20`. Renders as an empty string if it holds and fails with
+/// a format error otherwise, so the next alternative of the template is tried.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Condition {
+    lhs: Expr,
+    op: CmpOp,
+    rhs: Expr,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum CmpOp {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
 }
 
 impl FormatTemplate {
@@ -36,14 +63,21 @@ impl FormatTemplate {
             token_list.0.iter().any(|token| match token {
                 Token::Var { name, .. } => name == key,
                 Token::Recursive(rec) => rec.contains_key(key),
+                Token::Expr { expr, .. } => expr.contains_key(key),
+                Token::Condition(cond) => cond.contains_key(key),
                 _ => false,
             })
         })
     }
 
-    pub fn render(&self, vars: &HashMap<String, Value>) -> Result<String> {
+    pub fn render(
+        &self,
+        vars: &HashMap<String, Value>,
+        history: &History,
+        config: &SharedConfig,
+    ) -> Result<String> {
         for (i, token_list) in self.0.iter().enumerate() {
-            match token_list.render(vars) {
+            match token_list.render(vars, history, config) {
                 Ok(res) => return Ok(res),
                 Err(e) if e.kind != ErrorKind::Format => return Err(e),
                 Err(e) if i == self.0.len() - 1 => return Err(e),
@@ -53,6 +87,35 @@ impl FormatTemplate {
         Ok(String::new())
     }
 
+    /// Register the placeholders and expressions whose previous values have to be kept
+    pub fn track_history(&self, history: &mut History) {
+        for tl in &self.0 {
+            for t in &tl.0 {
+                match t {
+                    Token::Recursive(r) => r.track_history(history),
+                    Token::Var {
+                        name,
+                        formatter: Some(f),
+                    } => {
+                        if f.history_len() > 0 {
+                            history.track(name.clone(), Expr::Var(name.clone()), f.history_len());
+                        }
+                        if let Some(item) = f.item_history() {
+                            history.track_items(name.clone(), item);
+                        }
+                    }
+                    Token::Expr {
+                        expr,
+                        formatter: Some(f),
+                    } if f.history_len() > 0 => {
+                        history.track(expr_key(expr), expr.clone(), f.history_len());
+                    }
+                    _ => (),
+                }
+            }
+        }
+    }
+
     pub fn init(&self, tx: &Sender<Request>, block_id: usize, handles: &mut super::Handles) {
         for tl in &self.0 {
             for t in &tl.0 {
@@ -60,6 +123,9 @@ impl FormatTemplate {
                     Token::Recursive(r) => r.init(tx, block_id, handles),
                     Token::Var {
                         formatter: Some(f), ..
+                    }
+                    | Token::Expr {
+                        formatter: Some(f), ..
                     } => f.init(tx, block_id, handles),
                     _ => (),
                 }
@@ -69,27 +135,49 @@ impl FormatTemplate {
 }
 
 impl TokenList {
-    pub fn render(&self, vars: &HashMap<String, Value>) -> Result<String> {
+    pub fn render(
+        &self,
+        vars: &HashMap<String, Value>,
+        history: &History,
+        config: &SharedConfig,
+    ) -> Result<String> {
         let mut retval = String::new();
         for token in &self.0 {
             match token {
                 Token::Text(text) => retval.push_str(text),
-                Token::Recursive(rec) => retval.push_str(&rec.render(vars)?),
+                Token::Recursive(rec) => retval.push_str(&rec.render(vars, history, config)?),
+                Token::Condition(cond) => {
+                    if !cond.eval(vars)? {
+                        return Err(Error::new_format("Condition is not satisfied"));
+                    }
+                }
                 Token::Var { name, formatter } => {
                     let var = vars
                         .get(name)
                         .format_error(format!("Placeholder with name '{}' not found", name))?;
-                    let formatter =
-                        formatter
-                            .as_ref()
-                            .map(|x| x.as_ref())
-                            .unwrap_or_else(|| match var {
-                                Value::Text(_) => &DEFAULT_STRING_FORMATTER,
-                                Value::Icon(_) => &DEFAULT_STRING_FORMATTER,
-                                Value::Number { .. } => &DEFAULT_NUMBER_FORMATTER,
-                                Value::Flag => &DEFAULT_FLAG_FORMATTER,
-                            });
-                    retval.push_str(&formatter.format(var)?);
+                    let formatter = formatter
+                        .as_ref()
+                        .map(|x| x.as_ref())
+                        .unwrap_or_else(|| default_formatter(var));
+                    retval.push_str(&match (history.get(name), history.get_items(name)) {
+                        (Some(values), _) => formatter.format_history(var, values, config)?,
+                        (None, Some(items)) => formatter.format_items(var, items, config)?,
+                        (None, None) => formatter.format(var, config)?,
+                    });
+                }
+                Token::Expr { expr, formatter } => {
+                    let val = expr.eval_value(vars)?;
+                    let formatter = formatter
+                        .as_ref()
+                        .map(|x| x.as_ref())
+                        .unwrap_or_else(|| default_formatter(&val));
+                    retval.push_str(&match formatter.history_len() {
+                        0 => formatter.format(&val, config)?,
+                        _ => match history.get(&expr_key(expr)) {
+                            Some(values) => formatter.format_history(&val, values, config)?,
+                            None => formatter.format(&val, config)?,
+                        },
+                    });
                 }
             }
         }
@@ -97,21 +185,188 @@ impl TokenList {
     }
 }
 
+/// The key of an expression in the history
+fn expr_key(expr: &Expr) -> String {
+    format!("{:?}", expr).into()
+}
+
+impl Condition {
+    fn contains_key(&self, key: &str) -> bool {
+        self.lhs.contains_key(key) || self.rhs.contains_key(key)
+    }
+
+    fn eval(&self, vars: &HashMap<String, Value>) -> Result<bool> {
+        let (lhs, _) = self.lhs.eval(vars)?;
+        let (rhs, _) = self.rhs.eval(vars)?;
+        Ok(match self.op {
+            CmpOp::Lt => lhs < rhs,
+            CmpOp::Le => lhs <= rhs,
+            CmpOp::Gt => lhs > rhs,
+            CmpOp::Ge => lhs >= rhs,
+            CmpOp::Eq => lhs == rhs,
+            CmpOp::Ne => lhs != rhs,
+        })
+    }
+}
+
+impl FromStr for Condition {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        const OPS: &[(&str, CmpOp)] = &[
+            ("<=", CmpOp::Le),
+            (">=", CmpOp::Ge),
+            ("==", CmpOp::Eq),
+            ("!=", CmpOp::Ne),
+            ("<", CmpOp::Lt),
+            (">", CmpOp::Gt),
+        ];
+        let pos = s
+            .find(|c| matches!(c, '<' | '>' | '=' | '!'))
+            .or_error(|| format!("Condition '{}' has no comparison operator", s))?;
+        let (lhs, rest) = s.split_at(pos);
+        let (op_len, op) = OPS
+            .iter()
+            .find(|(op, _)| rest.starts_with(op))
+            .map(|(name, op)| (name.len(), *op))
+            .or_error(|| format!("Unknown comparison operator in '{}'", s))?;
+        Ok(Self {
+            lhs: lhs.parse()?,
+            op,
+            rhs: rest[op_len..].parse()?,
+        })
+    }
+}
+
+/// An error in a format template, pointing at the offending part of it
+#[derive(Debug, Clone)]
+pub struct ParseError {
+    pub template: String,
+    /// Byte offset of `token` in `template`
+    pub offset: usize,
+    pub token: String,
+    pub message: String,
+}
+
+impl ParseError {
+    /// The line and column (both starting at 1) of the offending token
+    pub fn line_column(&self) -> (usize, usize) {
+        let before = &self.template[..self.offset];
+        let line = before.matches('\n').count() + 1;
+        let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+        (line, before[line_start..].chars().count() + 1)
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let (line, column) = self.line_column();
+        write!(
+            f,
+            "{} at {}:{} of {:?}",
+            self.message, line, column, self.template
+        )
+    }
+}
+
+impl StdError for ParseError {}
+
+impl From<ParseError> for Error {
+    fn from(err: ParseError) -> Self {
+        Self {
+            kind: ErrorKind::Config,
+            message: Some(err.to_string().into()),
+            cause: Some(Arc::new(err)),
+            block: None,
+        }
+    }
+}
+
+/// The characters of a template being parsed. Keeps track of the position for error messages.
+struct Input<'a> {
+    template: &'a str,
+    chars: Peekable<Chain<CharIndices<'a>, Once<(usize, char)>>>,
+    /// The offset of the last consumed character
+    last: usize,
+}
+
+impl<'a> Input<'a> {
+    fn new(template: &'a str) -> Self {
+        Self {
+            template,
+            // The template is parsed as if it was enclosed in `{}`
+            chars: template
+                .char_indices()
+                .chain(std::iter::once((template.len(), '}')))
+                .peekable(),
+            last: 0,
+        }
+    }
+
+    fn peek(&mut self) -> Option<&char> {
+        self.chars.peek().map(|(_, c)| c)
+    }
+
+    /// The offset of the next character
+    fn offset(&mut self) -> usize {
+        self.chars
+            .peek()
+            .map_or(self.template.len(), |&(i, _)| i)
+            .min(self.template.len())
+    }
+
+    fn error(&self, offset: usize, end: usize, message: String) -> Error {
+        ParseError {
+            template: self.template.into(),
+            offset,
+            token: self.template[offset..end].into(),
+            message,
+        }
+        .into()
+    }
+
+    /// Point errors which are not yet located at the token which starts at `start` and ends
+    /// before the next character
+    fn locate<T>(&mut self, start: usize, result: Result<T>) -> Result<T> {
+        result.map_err(|err| {
+            if err.cause.as_ref().map_or(false, |c| c.is::<ParseError>()) {
+                err
+            } else {
+                let end = self.offset();
+                self.error(start, end, err.to_string().into())
+            }
+        })
+    }
+}
+
+impl Iterator for Input<'_> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        let (offset, c) = self.chars.next()?;
+        self.last = offset.min(self.template.len());
+        Some(c)
+    }
+}
+
 impl FromStr for FormatTemplate {
     type Err = Error;
 
     fn from_str(s: &str) -> Result<Self> {
-        let mut it = s.chars().chain(std::iter::once('}')).peekable();
-        let template = read_format_template(&mut it)?;
+        let mut it = Input::new(s);
+        let template = read_format_template(&mut it);
+        let template = it.locate(s.len(), template)?;
+        // The template may only be closed by the implicit `}` at the end
+        let closing = it.last;
         if it.next().is_some() {
-            Err(Error::new("Unexpected '}'"))
+            Err(it.error(closing, closing + 1, "Unexpected '}'".into()))
         } else {
             Ok(template)
         }
     }
 }
 
-fn read_format_template(it: &mut Peekable<impl Iterator<Item = char>>) -> Result<FormatTemplate> {
+fn read_format_template(it: &mut Input) -> Result<FormatTemplate> {
     let mut token_lists = Vec::new();
     let mut cur_list = Vec::new();
     loop {
@@ -131,16 +386,9 @@ fn read_format_template(it: &mut Peekable<impl Iterator<Item = char>>) -> Result
                 cur_list = Vec::new();
             }
             '$' => {
-                let _ = it.next();
-                let name = read_placeholder_name(it)?;
-                let formatter = match it.peek() {
-                    Some('.') => {
-                        let _ = it.next();
-                        Some(new_formatter(&read_formatter(it)?, &read_args(it)?)?)
-                    }
-                    _ => None,
-                };
-                cur_list.push(Token::Var { name, formatter });
+                let start = it.offset();
+                let token = read_placeholder(it);
+                cur_list.push(it.locate(start, token)?);
             }
             _ => {
                 cur_list.push(Token::Text(read_text(it)?));
@@ -149,7 +397,33 @@ fn read_format_template(it: &mut Peekable<impl Iterator<Item = char>>) -> Result
     }
 }
 
-fn read_text(it: &mut Peekable<impl Iterator<Item = char>>) -> Result<String> {
+/// Read a placeholder, a condition or an expression
+fn read_placeholder(it: &mut Input) -> Result<Token> {
+    let _ = it.next();
+    match it.peek() {
+        Some('{') => {
+            let _ = it.next();
+            let condition = read_condition(it)?;
+            match flag_alternatives(&condition) {
+                Some(alternatives) => Ok(Token::Recursive(alternatives)),
+                None => Ok(Token::Condition(condition.parse()?)),
+            }
+        }
+        Some('(') => {
+            let _ = it.next();
+            let expr = read_expr(it)?.parse()?;
+            let formatter = read_formatter_call(it)?;
+            Ok(Token::Expr { expr, formatter })
+        }
+        _ => {
+            let name = read_placeholder_name(it)?;
+            let formatter = read_formatter_call(it)?;
+            Ok(Token::Var { name, formatter })
+        }
+    }
+}
+
+fn read_text(it: &mut Input) -> Result<String> {
     let mut retval = String::new();
     let mut escaped = false;
     while let Some(&c) = it.peek() {
@@ -174,7 +448,88 @@ fn read_text(it: &mut Peekable<impl Iterator<Item = char>>) -> Result<String> {
     Ok(retval)
 }
 
-fn read_placeholder_name(it: &mut Peekable<impl Iterator<Item = char>>) -> Result<String> {
+fn read_condition(it: &mut Input) -> Result<String> {
+    let mut retval = String::new();
+    for c in it {
+        match c {
+            '}' => return Ok(retval),
+            x => retval.push(x),
+        }
+    }
+    Err(Error::new("Missing '}'"))
+}
+
+/// `${a|b}` is the old syntax for `{$a|$b}`, which is set if any of the flags is set. It is told
+/// apart from a condition by having no `$` and no comparison operator.
+fn flag_alternatives(s: &str) -> Option<FormatTemplate> {
+    s.split('|')
+        .map(|name| {
+            let name = name.trim();
+            let valid = !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+            valid.then(|| {
+                TokenList(vec![Token::Var {
+                    name: name.into(),
+                    formatter: None,
+                }])
+            })
+        })
+        .collect::<Option<_>>()
+        .map(FormatTemplate)
+}
+
+/// Read the contents of `$(...)`, the opening parenthesis is already consumed
+fn read_expr(it: &mut Input) -> Result<String> {
+    let mut retval = String::new();
+    let mut depth = 0;
+    for c in it {
+        match c {
+            ')' if depth == 0 => return Ok(retval),
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            _ => (),
+        }
+        retval.push(c);
+    }
+    Err(Error::new("Missing ')'"))
+}
+
+/// Read an optional `.formatter(args)` after a placeholder or an expression
+fn read_formatter_call(it: &mut Input) -> Result<Option<Box<dyn Formatter + Send + Sync>>> {
+    if it.peek() != Some(&'.') {
+        return Ok(None);
+    }
+    let _ = it.next();
+    let start = it.offset();
+    let formatter = read_formatter_with_args(it);
+    it.locate(start, formatter).map(Some)
+}
+
+fn read_formatter_with_args(it: &mut Input) -> Result<Box<dyn Formatter + Send + Sync>> {
+    let formatter = read_formatter(it)?;
+    if formatter.as_str() == "each" && it.peek() == Some(&'{') {
+        let _ = it.next();
+        let template = read_format_template(it)?;
+        // The rest is `)` or `,<separator>)`
+        let args = read_args(it)?;
+        match args.as_slice() {
+            [] => (),
+            [stray, ..] if !stray.is_empty() => {
+                return Err(Error::new(format!(
+                    "Unexpected '{}' after the template of each()",
+                    stray
+                )))
+            }
+            [_] | [_, _] => (),
+            _ => return Err(Error::new("each() takes at most two arguments")),
+        }
+        let separator = args.get(1).cloned().unwrap_or_default();
+        Ok(Box::new(EachFormatter::new(template, separator)))
+    } else {
+        new_formatter(&formatter, &read_args(it)?)
+    }
+}
+
+fn read_placeholder_name(it: &mut Input) -> Result<String> {
     let mut retval = String::new();
     let mut escaped = false;
     while let Some(&c) = it.peek() {
@@ -199,7 +554,7 @@ fn read_placeholder_name(it: &mut Peekable<impl Iterator<Item = char>>) -> Resul
     Ok(retval)
 }
 
-fn read_formatter(it: &mut impl Iterator<Item = char>) -> Result<String> {
+fn read_formatter(it: &mut Input) -> Result<String> {
     let mut retval = String::new();
     let mut escaped = false;
     for c in it {
@@ -217,7 +572,7 @@ fn read_formatter(it: &mut impl Iterator<Item = char>) -> Result<String> {
     Err(Error::new("Missing '('"))
 }
 
-fn read_args(it: &mut impl Iterator<Item = char>) -> Result<Vec<String>> {
+fn read_args(it: &mut Input) -> Result<Vec<String>> {
     let mut args = Vec::new();
     let mut cur_arg = String::new();
     let mut escaped = false;
@@ -244,3 +599,156 @@ fn read_args(it: &mut impl Iterator<Item = char>) -> Result<Vec<String>> {
     }
     Err(Error::new("Missing ')'"))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::formatting::unit::Unit;
+
+    fn render(format: &str, vars: &HashMap<String, Value>) -> std::string::String {
+        let template: FormatTemplate = format.parse().unwrap();
+        template
+            .render(vars, &History::default(), &SharedConfig::default())
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn conditions() {
+        let low = map! { "percentage" => Value::percents(10) };
+        let high = map! { "percentage" => Value::percents(50) };
+        let format = "${$percentage < 20}{LOW}|${$percentage >= 20}{HIGH}";
+        assert_eq!(render(format, &low), "LOW");
+        assert_eq!(render(format, &high), "HIGH");
+        assert_eq!(render("${$x == 1}{one}|other", &low), "other");
+        assert_eq!(render("${50 != $percentage}{ne}|eq", &high), "eq");
+    }
+
+    #[test]
+    fn flag_alternatives() {
+        let format = "${a|b}{a or b}|neither";
+        assert_eq!(render(format, &map! { "a" => Value::Flag }), "a or b");
+        assert_eq!(render(format, &map! { "b" => Value::Flag }), "a or b");
+        assert_eq!(render(format, &map! { "c" => Value::Flag }), "neither");
+        // Same as the new syntax
+        let format = "{$a|$b}{a or b}|neither";
+        assert_eq!(render(format, &map! { "b" => Value::Flag }), "a or b");
+        assert_eq!(render(format, &map! { "c" => Value::Flag }), "neither");
+        assert!("${a|}{x}".parse::<FormatTemplate>().is_err());
+        assert!("${a|$b}{x}".parse::<FormatTemplate>().is_err());
+    }
+
+    #[test]
+    fn invalid_conditions() {
+        assert!("${$a}{x}".parse::<FormatTemplate>().is_err());
+        assert!("${$a <> 1}{x}".parse::<FormatTemplate>().is_err());
+        assert!("${$a < b}{x}".parse::<FormatTemplate>().is_err());
+        assert!("${$a < 1".parse::<FormatTemplate>().is_err());
+    }
+
+    fn parse_error(format: &str) -> ParseError {
+        let err = format.parse::<FormatTemplate>().unwrap_err();
+        err.cause
+            .unwrap()
+            .downcast_ref::<ParseError>()
+            .unwrap()
+            .clone()
+    }
+
+    #[test]
+    fn parse_errors() {
+        let err = parse_error("$a.foo() $b");
+        assert_eq!((err.offset, err.token.as_str()), (3, "foo()"));
+        assert_eq!(err.line_column(), (1, 4));
+        assert!(err.to_string().ends_with(" at 1:4 of \"$a.foo() $b\""));
+
+        let err = parse_error("ab ${$x <> 1}");
+        assert_eq!((err.offset, err.token.as_str()), (3, "${$x <> 1}"));
+
+        let err = parse_error("{$a");
+        assert_eq!((err.offset, err.token.as_str()), (3, ""));
+        assert_eq!(err.message, "Missing '}'");
+
+        let err = parse_error("$a}b");
+        assert_eq!((err.offset, err.token.as_str()), (2, "}"));
+
+        let err = parse_error("$a\nä $b.foo()");
+        assert_eq!(err.line_column(), (2, 6));
+        assert!(!err.to_string().contains('\n'));
+    }
+
+    #[test]
+    fn expressions() {
+        let vars = map! {
+            "total" => Value::bytes(3000),
+            "avail" => Value::bytes(1000),
+            "running" => Value::number(2),
+            "stopped" => Value::number(3),
+        };
+        assert_eq!(render("$($running + $stopped)", &vars), " 5");
+        assert_eq!(render("$($running * ($stopped - 1)).eng(1)", &vars), "4");
+        assert_eq!(
+            render("$(max($running, $stopped) / 2).eng(3)", &vars),
+            "1.5"
+        );
+        assert_eq!(render("$($total - $avail).eng(1,B,K)", &vars), "2KB");
+        assert_eq!(render("{$($running / 0)|inf}", &vars), "inf");
+        assert_eq!(
+            render("${$running + $stopped > 4}{many}|few", &vars),
+            "many"
+        );
+
+        let temps = map! {
+            "cpu" => Value::degrees(50),
+            "outside" => Value::degrees(20),
+            "ambient" => Value::number_unit(293.15, Unit::Kelvin),
+        };
+        assert_eq!(render("${$cpu - $outside == 30}{ok}|bad", &temps), "ok");
+        assert_eq!(render("${$cpu - 30 == $outside}{ok}|bad", &temps), "ok");
+        let template: FormatTemplate = "$($cpu - $ambient)".parse().unwrap();
+        assert!(template
+            .render(&temps, &History::default(), &SharedConfig::default())
+            .is_err());
+        assert_eq!(render("${max($cpu, $ambient) == 50}{ok}|bad", &temps), "ok");
+
+        assert!("$($a +)".parse::<FormatTemplate>().is_err());
+        assert!("$(foo($a))".parse::<FormatTemplate>().is_err());
+        assert!("$(min($a))".parse::<FormatTemplate>().is_err());
+        assert!("$($a".parse::<FormatTemplate>().is_err());
+    }
+
+    #[test]
+    fn binary_prefixes() {
+        let vars = map! { "mem" => Value::bytes(3. * 1024. * 1024.) };
+        let template: FormatTemplate = "$mem.eng(3)".parse().unwrap();
+        let config = SharedConfig {
+            binary_prefixes: true,
+            ..Default::default()
+        };
+        let rendered = template.render(&vars, &History::default(), &config);
+        assert_eq!(rendered.unwrap(), "3.0MiB");
+        assert_eq!(render("$mem.eng(3)", &vars), "3.1MB");
+        assert_eq!(render("$mem.eng(3,B,Ki)", &vars), "3.0MiB");
+    }
+
+    #[test]
+    fn lists() {
+        let vars = map! {
+            "marks" => Value::list(vec![Value::text("a".into()), Value::text("b".into())]),
+            "empty" => Value::list(Vec::new()),
+        };
+        assert_eq!(render("$marks", &vars), "a b");
+        assert_eq!(render("$marks.join(\\, )", &vars), "a, b");
+        assert_eq!(render("$marks.count()", &vars), "2");
+        assert_eq!(render("$marks.first()", &vars), "a");
+        assert_eq!(render("$marks.at(1)", &vars), "b");
+        assert_eq!(render("{$marks.at(2)|none}", &vars), "none");
+        assert_eq!(render("{$empty.first()|none}", &vars), "none");
+        assert_eq!(render("$marks.each({[$item]})", &vars), "[a][b]");
+        assert_eq!(render("$marks.each({<$item>}, )", &vars), "<a> <b>");
+        assert!("$marks.each({$item}x, )".parse::<FormatTemplate>().is_err());
+        assert!("$marks.each({$item}, a, b)"
+            .parse::<FormatTemplate>()
+            .is_err());
+    }
+}
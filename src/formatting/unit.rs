@@ -12,14 +12,36 @@ pub enum Unit {
     Bits,
     /// `%`
     Percents,
-    /// `deg`
+    /// `deg` (degrees Celsius)
     Degrees,
+    /// `degF`
+    Fahrenheit,
+    /// `K`
+    Kelvin,
     /// `s`
     Seconds,
+    /// `min`
+    Minutes,
+    /// `h`
+    Hours,
     /// `W`
     Watts,
+    /// `Wh`
+    WattHours,
+    /// `V`
+    Volts,
+    /// `A`
+    Amperes,
     /// `Hz`
     Hertz,
+    /// `RPM`
+    Rpm,
+    /// `m/s`
+    MetersPerSecond,
+    /// `km/h`
+    KilometersPerHour,
+    /// `mph`
+    MilesPerHour,
     /// ``
     None,
 }
@@ -31,9 +53,20 @@ impl fmt::Display for Unit {
             Self::Bits => "b",
             Self::Percents => "%",
             Self::Degrees => "°",
+            Self::Fahrenheit => "°F",
+            Self::Kelvin => "K",
             Self::Seconds => "s",
+            Self::Minutes => "min",
+            Self::Hours => "h",
             Self::Watts => "W",
+            Self::WattHours => "Wh",
+            Self::Volts => "V",
+            Self::Amperes => "A",
             Self::Hertz => "Hz",
+            Self::Rpm => "RPM",
+            Self::MetersPerSecond => "m/s",
+            Self::KilometersPerHour => "km/h",
+            Self::MilesPerHour => "mph",
             Self::None => "",
         })
     }
@@ -47,10 +80,21 @@ impl FromStr for Unit {
             "B" => Ok(Unit::Bytes),
             "b" => Ok(Unit::Bits),
             "%" => Ok(Unit::Percents),
-            "deg" => Ok(Unit::Degrees),
+            "deg" | "degC" => Ok(Unit::Degrees),
+            "degF" => Ok(Unit::Fahrenheit),
+            "K" => Ok(Unit::Kelvin),
             "s" => Ok(Unit::Seconds),
+            "min" => Ok(Unit::Minutes),
+            "h" => Ok(Unit::Hours),
             "W" => Ok(Unit::Watts),
+            "Wh" => Ok(Unit::WattHours),
+            "V" => Ok(Unit::Volts),
+            "A" => Ok(Unit::Amperes),
             "Hz" => Ok(Unit::Hertz),
+            "RPM" => Ok(Unit::Rpm),
+            "m/s" => Ok(Unit::MetersPerSecond),
+            "km/h" => Ok(Unit::KilometersPerHour),
+            "mph" => Ok(Unit::MilesPerHour),
             "" => Ok(Unit::None),
             x => Err(Error::new(format!("Unknown unit: '{}'", x))),
         }
@@ -59,22 +103,117 @@ impl FromStr for Unit {
 
 impl Unit {
     pub fn convert(self, value: f64, unit: Self) -> Result<f64> {
-        match (self, unit) {
-            (x, y) if x == y => Ok(value),
-            (Self::Bytes, Self::Bits) => Ok(value * 8.),
-            (Self::Bits, Self::Bytes) => Ok(value / 8.),
-            _ => Err(Error::new(format!(
+        if self.base() == unit.base() {
+            Ok(unit.from_base(self.to_base(value)))
+        } else {
+            Err(Error::new(format!(
                 "Failed to convert '{}' to '{}",
                 self, unit
-            ))),
+            )))
         }
     }
 
+    /// The unit which all units of the same quantity are converted through
+    fn base(self) -> Self {
+        match self {
+            Self::Bytes | Self::Bits => Self::Bytes,
+            Self::Degrees | Self::Fahrenheit | Self::Kelvin => Self::Degrees,
+            Self::Seconds | Self::Minutes | Self::Hours => Self::Seconds,
+            Self::MetersPerSecond | Self::KilometersPerHour | Self::MilesPerHour => {
+                Self::MetersPerSecond
+            }
+            x => x,
+        }
+    }
+
+    fn to_base(self, value: f64) -> f64 {
+        match self {
+            Self::Bits => value / 8.,
+            Self::Fahrenheit => (value - 32.) / 1.8,
+            Self::Kelvin => value - 273.15,
+            Self::Minutes => value * 60.,
+            Self::Hours => value * 3600.,
+            Self::KilometersPerHour => value / 3.6,
+            Self::MilesPerHour => value * 0.44704,
+            _ => value,
+        }
+    }
+
+    #[allow(clippy::wrong_self_convention)]
+    fn from_base(self, value: f64) -> f64 {
+        match self {
+            Self::Bits => value * 8.,
+            Self::Fahrenheit => value * 1.8 + 32.,
+            Self::Kelvin => value + 273.15,
+            Self::Minutes => value / 60.,
+            Self::Hours => value / 3600.,
+            Self::KilometersPerHour => value * 3.6,
+            Self::MilesPerHour => value / 0.44704,
+            _ => value,
+        }
+    }
+
+    /// Whether binary prefixes make sense for this unit
+    pub fn is_temperature(self) -> bool {
+        self.base() == Self::Degrees
+    }
+
+    pub fn allows_binary_prefix(self) -> bool {
+        matches!(self, Self::Bytes | Self::Bits)
+    }
+
     pub fn clamp_prefix(self, prefix: Prefix) -> Prefix {
         match self {
             Self::Bytes | Self::Bits => prefix.max(Prefix::One),
-            Self::Percents | Self::None => Prefix::One,
-            _ => prefix,
+            Self::Percents
+            | Self::Degrees
+            | Self::Fahrenheit
+            | Self::Kelvin
+            | Self::Minutes
+            | Self::Hours
+            | Self::Rpm
+            | Self::MetersPerSecond
+            | Self::KilometersPerHour
+            | Self::MilesPerHour
+            | Self::None => Prefix::One,
+            _ => prefix.si(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-9, "{} != {}", a, b);
+    }
+
+    #[test]
+    fn base_conversions() {
+        let cases = [
+            (Unit::Bits, 16., 2.),
+            (Unit::Fahrenheit, 212., 100.),
+            (Unit::Kelvin, 273.15, 0.),
+            (Unit::Minutes, 2., 120.),
+            (Unit::Hours, 1.5, 5400.),
+            (Unit::KilometersPerHour, 36., 10.),
+            (Unit::MilesPerHour, 1., 0.44704),
+            (Unit::Watts, 5., 5.),
+        ];
+        for (unit, value, base) in cases {
+            assert_close(unit.to_base(value), base);
+            assert_close(unit.from_base(base), value);
         }
     }
+
+    #[test]
+    fn convert() {
+        assert_close(Unit::Degrees.convert(-40., Unit::Fahrenheit).unwrap(), -40.);
+        assert_close(Unit::Fahrenheit.convert(32., Unit::Kelvin).unwrap(), 273.15);
+        assert_close(Unit::Bytes.convert(1., Unit::Bits).unwrap(), 8.);
+        assert_close(Unit::Hours.convert(1., Unit::Seconds).unwrap(), 3600.);
+        assert!(Unit::Bytes.convert(1., Unit::Seconds).is_err());
+        assert!(Unit::Watts.convert(1., Unit::WattHours).is_err());
+    }
 }
@@ -1,11 +1,16 @@
 use super::unit::Unit;
+use chrono::{DateTime, Local};
 use smartstring::alias::String;
+use std::time::Duration;
 
 #[derive(Debug, Clone)]
 pub enum Value {
     Text(String),
     Icon(String),
     Number { val: f64, unit: Unit, icon: String },
+    Duration(Duration),
+    Timestamp(DateTime<Local>),
+    List(Vec<Value>),
     Flag,
 }
 
@@ -61,9 +66,22 @@ impl Value {
     pub fn hertz(val: impl IntoF64) -> Self {
         Self::number_unit(val, Unit::Hertz)
     }
+    pub fn meters_per_second(val: impl IntoF64) -> Self {
+        Self::number_unit(val, Unit::MetersPerSecond)
+    }
     pub fn number(val: impl IntoF64) -> Self {
         Self::number_unit(val, Unit::None)
     }
+
+    pub fn duration(dur: Duration) -> Self {
+        Self::Duration(dur)
+    }
+    pub fn timestamp(ts: DateTime<Local>) -> Self {
+        Self::Timestamp(ts)
+    }
+    pub fn list(items: Vec<Value>) -> Self {
+        Self::List(items)
+    }
 }
 
 /// Set options
@@ -75,3 +93,43 @@ impl Value {
         }
     }
 }
+
+/// Getters
+impl Value {
+    /// The value without any formatting, e.g. `45` for `45%`. Used to pass values to programs.
+    pub fn to_raw_string(&self) -> String {
+        match self {
+            Self::Text(text) | Self::Icon(text) => text.clone(),
+            Self::Number { val, .. } => val.to_string().into(),
+            Self::Duration(dur) => dur.as_secs().to_string().into(),
+            Self::Timestamp(ts) => ts.to_rfc3339().into(),
+            Self::List(items) => {
+                let items: Vec<_> = items.iter().map(Self::to_raw_string).collect();
+                items.join(" ").into()
+            }
+            Self::Flag => "1".into(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_raw_string() {
+        assert_eq!(Value::text("foo bar".into()).to_raw_string(), "foo bar");
+        assert_eq!(Value::Icon("icon".into()).to_raw_string(), "icon");
+        assert_eq!(Value::percents(45).to_raw_string(), "45");
+        assert_eq!(Value::bytes(1.5).to_raw_string(), "1.5");
+        assert_eq!(
+            Value::duration(Duration::from_millis(61_500)).to_raw_string(),
+            "61"
+        );
+        assert_eq!(
+            Value::list(vec![Value::text("a".into()), Value::number(2)]).to_raw_string(),
+            "a 2"
+        );
+        assert_eq!(Value::Flag.to_raw_string(), "1");
+    }
+}
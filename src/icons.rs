@@ -28,6 +28,15 @@ impl Default for Icons {
             "backlight_12" => "BRIGHT",
             "backlight_13" => "BRIGHT",
             "bat" => "BAT",
+            "bat_10" => "BAT",
+            "bat_20" => "BAT",
+            "bat_30" => "BAT",
+            "bat_40" => "BAT",
+            "bat_50" => "BAT",
+            "bat_60" => "BAT",
+            "bat_70" => "BAT",
+            "bat_80" => "BAT",
+            "bat_90" => "BAT",
             "bat_charging" => "CHG",
             "bat_discharging" => "DCG",
             "bat_empty" => "EMP",
@@ -3,12 +3,14 @@ mod util;
 mod blocks;
 mod click;
 mod config;
+mod control;
 mod de;
 mod errors;
 mod escape;
 mod formatting;
 mod icons;
 mod netlink;
+mod power;
 mod protocol;
 mod signals;
 mod subprocess;
@@ -17,6 +19,7 @@ mod widget;
 mod wrappers;
 
 use clap::Parser;
+use futures::future::{AbortHandle, Abortable, Aborted};
 use futures::stream::futures_unordered::FuturesUnordered;
 use futures::stream::StreamExt;
 use futures::Future;
@@ -26,19 +29,24 @@ use protocol::i3bar_event::I3BarEvent;
 use smallvec::SmallVec;
 use smartstring::alias::String;
 use std::collections::HashMap;
+use std::path::PathBuf;
 use std::pin::Pin;
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::mpsc;
 use tokio::sync::oneshot::Sender as OneshotSender;
+use tokio::sync::{watch, Notify};
+use tokio::time::Instant;
 
-use blocks::{BlockEvent, BlockType, CommonApi, CommonConfig};
-use click::ClickHandler;
-use config::Config;
+use blocks::{BlockAction, BlockEvent, BlockType, CommonApi, CommonConfig, Restart, RestartPolicy};
+use click::{ClickHandler, MouseButton};
+use config::config_updates_stream;
 use config::SharedConfig;
+use control::ControlCmd;
 use errors::*;
 use formatting::{value::Value, RunningFormat};
 use protocol::i3bar_event::events_stream;
+use protocol::Output;
 use signals::{signals_stream, Signal};
 use widget::{State, Widget};
 
@@ -50,6 +58,24 @@ pub static REQWEST_CLIENT: Lazy<reqwest::Client> = Lazy::new(|| {
         .unwrap()
 });
 
+/// Whether the bar is hidden and asked us to pause. Polling blocks and background tasks (e.g. the
+/// `rot-str` tickers) wait for the bar to resume before updating again.
+pub static BAR_PAUSED: Lazy<(watch::Sender<bool>, watch::Receiver<bool>)> =
+    Lazy::new(|| watch::channel(false));
+
+/// Wait until the bar is not paused
+pub async fn wait_until_resumed() {
+    let mut paused = BAR_PAUSED.1.clone();
+    while *paused.borrow() {
+        if paused.changed().await.is_err() {
+            break;
+        }
+    }
+}
+
+/// How long to wait for more changes before printing the bar
+const RENDER_DELAY: Duration = Duration::from_millis(16);
+
 #[derive(Debug, Parser)]
 #[clap(author, about, version = env!("VERSION"))]
 struct CliArgs {
@@ -68,6 +94,21 @@ struct CliArgs {
     /// The DBUS name
     #[clap(long = "dbus-name", default_value = "rs.swaystatus")]
     dbus_name: String,
+    /// Expose the control interface on the session bus, under the name given by `--dbus-name`
+    #[clap(long = "control")]
+    control: bool,
+    /// Check the config file, list all problems and exit
+    #[clap(long = "check")]
+    check: bool,
+    /// The config profile to use. Defaults to the value of `SWAYSTATUS_PROFILE`
+    #[clap(long = "profile")]
+    profile: Option<String>,
+    /// Print the config after merging all includes and overrides, then exit
+    #[clap(long = "print-config")]
+    print_config: bool,
+    /// The output format
+    #[clap(long = "output", arg_enum, default_value = "i3bar")]
+    output: Output,
 }
 
 fn main() {
@@ -76,23 +117,58 @@ fn main() {
     // console_subscriber::init();
     // }
 
-    let args = CliArgs::parse();
+    let mut args = CliArgs::parse();
     let blocking_threads = args.blocking_threads;
+    if args.profile.is_none() {
+        args.profile = std::env::var("SWAYSTATUS_PROFILE").ok().map(Into::into);
+    }
+
+    if args.print_config {
+        let merged = find_config(&args.config)
+            .and_then(|path| config::load_merged(&path, args.profile.as_deref()))
+            .and_then(|(config, _)| {
+                toml::to_string_pretty(&config).error("Failed to print config")
+            });
+        match merged {
+            Ok(merged) => print!("{}", merged),
+            Err(error) => {
+                eprintln!("{}", error);
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    if args.check {
+        std::process::exit(match check_config(&args.config, args.profile.as_deref()) {
+            Ok(()) => 0,
+            Err(errors) => {
+                for error in &errors {
+                    eprintln!("{}\n", error);
+                }
+                eprintln!("Found {} problem(s)", errors.len());
+                1
+            }
+        });
+    }
 
     if !args.no_init {
-        protocol::init(args.never_pause);
+        protocol::init(args.output, args.never_pause);
     }
 
     let result = (|| {
         // Read & parse the config file
-        let config_path = util::find_file(&args.config, None, Some("toml"))
-            .or_error(|| format!("Configuration file '{}' not found", args.config))?;
-        let config: Config = util::deserialize_toml_file(&config_path).config_error()?;
+        let config_path = find_config(&args.config)?;
+        let (config, config_files) =
+            config::load_config(&config_path, args.profile.as_deref()).config_error()?;
 
         // Spawn blocks
-        let mut swaystatus = BarState::new(config.shared, args);
+        let mut swaystatus = BarState::new(config.shared, args, config_path, config_files);
+        swaystatus.double_click_delay = Duration::from_millis(config.double_click_delay);
+        power::set_battery_factor(config.interval_on_battery);
         for (block_type, block_config) in config.block {
-            swaystatus.spawn_block(block_type, block_config)?;
+            let id = swaystatus.spawn_block(block_type, block_config)?;
+            swaystatus.layout.push(id);
         }
 
         // Run main loop
@@ -102,20 +178,34 @@ fn main() {
             .build()
             .unwrap()
             .block_on(async move {
-                let mut signals = signals_stream();
-                let mut events = events_stream(
-                    config.invert_scrolling,
-                    Duration::from_millis(config.double_click_delay),
-                );
-                swaystatus.run_event_loop(&mut signals, &mut events).await
+                let cli_args = &swaystatus.cli_args;
+                let mut signals =
+                    signals_stream(cli_args.output == Output::I3bar && !cli_args.never_pause);
+                let mut events = if swaystatus.cli_args.output.click_events() {
+                    events_stream(config.invert_scrolling)
+                } else {
+                    // Nothing sends click events, don't read stdin
+                    mpsc::channel(1).1
+                };
+                let mut config_updates = match config_updates_stream(&swaystatus.config_files) {
+                    Ok(receiver) => receiver,
+                    Err(error) => {
+                        // Run without live reload, e.g. when inotify's limits are reached
+                        eprintln!("Failed to watch config files: {}", error);
+                        mpsc::channel(1).1
+                    }
+                };
+                swaystatus
+                    .run_event_loop(&mut signals, &mut events, &mut config_updates)
+                    .await
             })
     })();
 
     if let Err(error) = result {
         let error_widget = Widget::new(0, Default::default()).with_text(error.to_string().into());
         println!(
-            "[{}],",
-            serde_json::to_string(&error_widget.get_data().unwrap()).unwrap()
+            "{},",
+            protocol::blocks_to_json(&[&[error_widget.get_data().unwrap()]], &Default::default())
         );
         eprintln!("\n\n{}\n\n", error);
         dbg!(error);
@@ -132,9 +222,24 @@ fn main() {
 
 pub struct RunningBlock {
     id: usize,
+    abort_handle: AbortHandle,
 
     event_sender: Option<mpsc::Sender<BlockEvent>>,
     click_handler: ClickHandler,
+    /// The maximum number of consecutive clicks the block itself wants to receive, per button
+    multi_clicks: HashMap<MouseButton, u8>,
+    actions: &'static [BlockAction],
+    signal: Option<Signal>,
+    refresh_on_resume: bool,
+    update_request: Arc<Notify>,
+
+    /// When the block has last sent a request
+    last_update: Instant,
+    stale_after: Option<Duration>,
+    stale_state: Option<State>,
+    stale_timeout: Option<Duration>,
+    /// The block has not updated for `stale_after`
+    stale: bool,
 
     hidden: bool,
     buttons_hidden: bool,
@@ -142,10 +247,79 @@ pub struct RunningBlock {
     buttons: Vec<Widget>,
 }
 
+impl RunningBlock {
+    /// The maximum number of consecutive clicks of `button` that should be combined into one event
+    fn max_clicks(&self, button: MouseButton) -> u8 {
+        let from_block = self.multi_clicks.get(&button).copied().unwrap_or(1);
+        self.click_handler.max_clicks(button).max(from_block)
+    }
+
+    /// The action the block binds to a single click of `button`
+    fn default_action(&self, button: MouseButton) -> Option<&'static str> {
+        self.actions
+            .iter()
+            .find(|(_, buttons)| buttons.contains(&button))
+            .map(|&(name, _)| name)
+    }
+
+    /// Resolve an action name to the block's declared action
+    fn find_action(&self, name: &str) -> Option<&'static str> {
+        self.actions
+            .iter()
+            .find(|(action, _)| *action == name)
+            .map(|&(action, _)| action)
+    }
+
+    /// When the block becomes stale or times out, unless it updates before
+    fn stale_deadline(&self) -> Option<Instant> {
+        let stale_at = self.stale_after.filter(|_| !self.stale);
+        let timeout_at = self.stale_timeout;
+        stale_at
+            .into_iter()
+            .chain(timeout_at)
+            .map(|after| self.last_update + after)
+            .min()
+    }
+
+    /// Dim the widget, or switch it to `stale_state`, if the block is stale
+    fn apply_stale(&self, data: &mut I3BarBlock) {
+        if !self.stale {
+            return;
+        }
+        match self.stale_state {
+            Some(state) => {
+                let (bg, fg) = self.widget.shared_config.theme.get_colors(state);
+                data.background = bg;
+                data.color = fg;
+            }
+            None => data.color = data.color.dimmed(),
+        }
+    }
+}
+
 pub struct FailedBlock {
     id: usize,
+    text: String,
     error_widget: Widget,
     error: Error,
+    /// When the block is going to be restarted (if ever)
+    restart_at: Option<Instant>,
+}
+
+impl FailedBlock {
+    fn update_widget(&mut self) {
+        match self.restart_at {
+            Some(restart_at) => {
+                let secs = restart_at
+                    .saturating_duration_since(Instant::now())
+                    .as_secs_f64()
+                    .ceil();
+                self.error_widget
+                    .set_text(format!("{} (retry in {}s)", self.text, secs).into());
+            }
+            None => self.error_widget.set_text(self.text.clone()),
+        }
+    }
 }
 
 pub enum Block {
@@ -153,6 +327,101 @@ pub enum Block {
     Failed(FailedBlock),
 }
 
+/// A block as seen by the main loop
+pub struct BlockHandle {
+    block: Block,
+    block_type: BlockType,
+    /// The original (unmodified) block config. Used to detect changes on config reloads.
+    config: toml::Value,
+    /// Hidden using the control interface
+    hidden_by_user: bool,
+    restart_policy: RestartPolicy,
+    /// The number of restarts since the block has last been running for a while
+    retries: u32,
+    started_at: Instant,
+    render_cache: Vec<I3BarBlock>,
+}
+
+impl BlockHandle {
+    fn set_shared_config(&mut self, shared_config: SharedConfig) {
+        match &mut self.block {
+            Block::Running(block) => {
+                for button in &mut block.buttons {
+                    button.shared_config = shared_config.clone();
+                }
+                block.widget.shared_config = shared_config;
+            }
+            Block::Failed(block) => block.error_widget.shared_config = shared_config,
+        }
+    }
+
+    /// Render the block's widgets with `shared_config`, to find out whether it can be applied
+    fn check_shared_config(&self, shared_config: &SharedConfig) -> Result<()> {
+        match &self.block {
+            Block::Running(block) => {
+                for widget in std::iter::once(&block.widget).chain(&block.buttons) {
+                    widget
+                        .get_data_with(shared_config)
+                        .in_block(self.block_type, block.id)?;
+                }
+            }
+            Block::Failed(block) => {
+                block.error_widget.get_data_with(shared_config)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Decide whether the block should be restarted after it has exited and return the delay
+    /// before the restart
+    fn next_restart(&mut self, failed: bool) -> Option<Duration> {
+        let policy = self.restart_policy;
+        // Consider the block recovered if it has been running longer than the last delay
+        if self.retries > 0 && self.started_at.elapsed() > policy.delay(self.retries - 1) {
+            self.retries = 0;
+        }
+        if !policy.should_restart(failed, self.retries) {
+            return None;
+        }
+        let delay = policy.delay(self.retries);
+        self.retries += 1;
+        Some(delay)
+    }
+
+    fn update_render_cache(&mut self) -> Result<()> {
+        let data = &mut self.render_cache;
+        data.clear();
+        if self.hidden_by_user {
+            return Ok(());
+        }
+        match &mut self.block {
+            Block::Running(block) => {
+                if !block.hidden {
+                    data.push(
+                        block
+                            .widget
+                            .get_data()
+                            .in_block(self.block_type, block.id)?,
+                    );
+                    if !block.buttons_hidden {
+                        for button in &block.buttons {
+                            data.push(button.get_data().in_block(self.block_type, block.id)?);
+                        }
+                    }
+                    for widget in data.iter_mut() {
+                        block.apply_stale(widget);
+                    }
+                }
+            }
+            Block::Failed(block) => {
+                block.update_widget();
+                data.push(block.error_widget.get_data()?);
+            }
+        }
+        Ok(())
+    }
+}
+
 #[derive(Debug)]
 pub struct Request {
     pub block_id: usize,
@@ -165,6 +434,8 @@ pub enum RequestCmd {
     Show,
 
     GetEvents(OneshotSender<blocks::EventsRx>),
+    ListenMultiClicks(MouseButton, u8),
+    SetActions(&'static [BlockAction]),
 
     SetIcon(String),
     SetState(State),
@@ -187,102 +458,384 @@ pub enum RequestCmd {
     GetDbusConnection(OneshotSender<Result<zbus::Connection>>),
     GetSystemDbusConnection(OneshotSender<Result<zbus::Connection>>),
 
+    /// Only re-render the block. Sent by background tasks, never by the block itself.
     Noop,
 }
 
+type BlockFuture = Pin<Box<dyn Future<Output = (usize, StdResult<Result<()>, Aborted>)>>>;
+
 struct BarState {
     shared_config: SharedConfig,
     cli_args: CliArgs,
+    config_path: PathBuf,
+    /// The config file and all the files it includes, which are watched for changes
+    config_files: Vec<PathBuf>,
 
-    blocks: Vec<(Block, BlockType)>,
+    /// Blocks by their IDs. IDs are never reused, so a block keeps its ID (and its widgets'
+    /// names) across config reloads.
+    blocks: HashMap<usize, BlockHandle>,
+    /// The IDs of blocks in the order they are displayed
+    layout: Vec<usize>,
+    next_block_id: usize,
     fullscreen_block: Option<usize>,
+    /// A click that may be followed by more clicks, and when to stop waiting for them
+    pending_click: Option<(I3BarEvent, Instant)>,
+    /// The maximum delay between two clicks that are considered as a multi-click
+    double_click_delay: Duration,
+    /// When to print the bar. Changes within [`RENDER_DELAY`] are printed together.
+    render_deadline: Option<Instant>,
+    /// The last printed line, to avoid printing the same line twice
+    last_line: std::string::String,
+    /// The bar is hidden. Nothing is printed and blocks' timers wait until it is resumed, but blocks
+    /// driven by events keep updating.
+    paused: bool,
     // TODO: find a way to avoid this `Box<dyn Future>`
-    running_blocks: FuturesUnordered<Pin<Box<dyn Future<Output = Result<()>>>>>,
-
-    blocks_render_cache: Vec<Vec<I3BarBlock>>,
+    running_blocks: FuturesUnordered<BlockFuture>,
+    /// Resolve to IDs of blocks that should be restarted
+    pending_restarts: FuturesUnordered<Pin<Box<dyn Future<Output = usize>>>>,
 
     request_sender: mpsc::Sender<Request>,
     request_receiver: mpsc::Receiver<Request>,
 
+    control_sender: mpsc::Sender<ControlCmd>,
+    control_receiver: mpsc::Receiver<ControlCmd>,
+
     dbus_connection: Option<zbus::Connection>,
     system_dbus_connection: Option<zbus::Connection>,
 }
 
 impl BarState {
-    fn new(shared_config: SharedConfig, cli: CliArgs) -> Self {
+    fn new(
+        shared_config: SharedConfig,
+        cli: CliArgs,
+        config_path: PathBuf,
+        config_files: Vec<PathBuf>,
+    ) -> Self {
         let (request_sender, request_receiver) = mpsc::channel(64);
+        let (control_sender, control_receiver) = mpsc::channel(16);
         Self {
             shared_config,
             cli_args: cli,
+            config_path,
+            config_files,
 
-            blocks: Vec::new(),
+            blocks: HashMap::new(),
+            layout: Vec::new(),
+            next_block_id: 0,
             fullscreen_block: None,
+            pending_click: None,
+            double_click_delay: Duration::from_millis(200),
+            render_deadline: None,
+            last_line: std::string::String::new(),
+            paused: false,
             running_blocks: FuturesUnordered::new(),
-
-            blocks_render_cache: Vec::new(),
+            pending_restarts: FuturesUnordered::new(),
 
             request_sender,
             request_receiver,
 
+            control_sender,
+            control_receiver,
+
             dbus_connection: None,
             system_dbus_connection: None,
         }
     }
 
-    fn spawn_block(&mut self, block_type: BlockType, mut block_config: toml::Value) -> Result<()> {
-        let common_config = CommonConfig::new(&mut block_config)?;
-        let mut shared_config = self.shared_config.clone();
+    /// Apply block's icons and theme overrides to the global shared config
+    fn block_shared_config(&self, common_config: &CommonConfig) -> Result<SharedConfig> {
+        common_config.shared_config(&self.shared_config)
+    }
 
-        // Overrides
-        if let Some(icons_format) = common_config.icons_format {
-            *Arc::make_mut(&mut shared_config.icons_format) = icons_format;
-        }
-        if let Some(theme_overrides) = common_config.theme_overrides {
-            Arc::make_mut(&mut shared_config.theme).apply_overrides(&theme_overrides)?;
-        }
+    /// Create a new block and return its ID. Note that the block is not added to the layout.
+    fn spawn_block(&mut self, block_type: BlockType, block_config: toml::Value) -> Result<usize> {
+        let id = self.next_block_id;
+        let (block, restart_policy) = self.start_block(id, block_type, &block_config)?;
+        self.next_block_id += 1;
+
+        self.blocks.insert(
+            id,
+            BlockHandle {
+                block: Block::Running(block),
+                block_type,
+                config: block_config,
+                hidden_by_user: false,
+                restart_policy,
+                retries: 0,
+                started_at: Instant::now(),
+                render_cache: Vec::new(),
+            },
+        );
+        Ok(id)
+    }
+
+    /// Run block's future
+    fn start_block(
+        &mut self,
+        id: usize,
+        block_type: BlockType,
+        block_config: &toml::Value,
+    ) -> Result<(RunningBlock, RestartPolicy)> {
+        let mut config = block_config.clone();
+        let common_config = CommonConfig::new(&mut config)?;
+        let shared_config = self.block_shared_config(&common_config)?;
+        let update_request = Arc::new(Notify::new());
 
         let api = CommonApi {
-            id: self.blocks.len(),
+            id,
             shared_config,
 
             request_sender: self.request_sender.clone(),
             cmd_buf: SmallVec::new(),
+            update_request: update_request.clone(),
 
             error_interval: Duration::from_secs(common_config.error_interval),
-            error_format: common_config.error_format,
+            error_format: common_config.error_format.clone(),
         };
 
-        let block = Block::Running(RunningBlock {
-            id: api.id,
+        let (abort_handle, abort_registration) = AbortHandle::new_pair();
+
+        let block = RunningBlock {
+            id,
+            abort_handle,
 
             event_sender: None,
             click_handler: common_config.click,
+            multi_clicks: HashMap::new(),
+            actions: &[],
+            signal: common_config.signal,
+            refresh_on_resume: common_config.refresh_on_resume,
+            update_request,
+
+            last_update: Instant::now(),
+            stale_after: common_config.stale_after.map(|s| s.0),
+            stale_state: common_config.stale_state,
+            stale_timeout: common_config.stale_timeout.map(|s| s.0),
+            stale: false,
 
             hidden: false,
             buttons_hidden: false,
-            widget: Widget::new(api.id, api.shared_config.clone()),
+            widget: Widget::new(id, api.shared_config.clone()),
             buttons: Vec::new(),
-        });
+        };
 
+        let restart_policy = common_config.restart_policy();
+        let block_future = Abortable::new(
+            power::with_battery_factor(
+                common_config.interval_on_battery,
+                block_type.run(config, api),
+            ),
+            abort_registration,
+        );
         self.running_blocks
-            .push(Box::pin(block_type.run(block_config, api)));
-        self.blocks.push((block, block_type));
-        self.blocks_render_cache.push(Vec::new());
+            .push(Box::pin(async move { (id, block_future.await) }));
+
+        Ok((block, restart_policy))
+    }
+
+    /// Restart a block at `restart_at`, showing a countdown until then
+    fn schedule_restart(&mut self, id: usize, restart_at: Instant) {
+        let request_sender = self.request_sender.clone();
+        self.pending_restarts.push(Box::pin(async move {
+            // Update the countdown every second
+            loop {
+                let now = Instant::now();
+                if now >= restart_at {
+                    break;
+                }
+                tokio::time::sleep_until(restart_at.min(now + Duration::from_secs(1))).await;
+                let mut cmds = SmallVec::new();
+                cmds.push(RequestCmd::Noop);
+                let _ = request_sender.send(Request { block_id: id, cmds }).await;
+            }
+            id
+        }));
+    }
+
+    /// Replace a block with an error and restart it later if its restart policy allows it
+    fn fail_block(&mut self, id: usize, error: Error, failed: bool) -> Result<()> {
+        let handle = match self.blocks.get_mut(&id) {
+            Some(handle) => handle,
+            // The block was removed by a config reload
+            None => return Ok(()),
+        };
+        let shared_config = match &handle.block {
+            Block::Running(block) => {
+                block.abort_handle.abort();
+                block.widget.shared_config.clone()
+            }
+            Block::Failed(block) => block.error_widget.shared_config.clone(),
+        };
+        let restart_at = handle
+            .next_restart(failed)
+            .map(|delay| Instant::now() + delay);
+        handle.block = Block::Failed(FailedBlock {
+            id,
+            text: error.message.as_deref().unwrap_or("Error").into(),
+            error_widget: Widget::new(id, shared_config).with_state(State::Critical),
+            error,
+            restart_at,
+        });
+        handle.update_render_cache()?;
+        if let Some(restart_at) = restart_at {
+            self.schedule_restart(id, restart_at);
+        }
+
+        self.fullscreen_block = None;
+        self.schedule_render();
+        Ok(())
+    }
+
+    /// Start a block again, reusing its ID and config
+    fn restart_block(&mut self, id: usize) -> Result<()> {
+        let (block_type, block_config) = match self.blocks.get(&id) {
+            Some(handle) => (handle.block_type, handle.config.clone()),
+            // The block was removed by a config reload
+            None => return Ok(()),
+        };
+        let (block, restart_policy) = self
+            .start_block(id, block_type, &block_config)
+            .in_block(block_type, id)?;
+
+        let handle = self.blocks.get_mut(&id).unwrap();
+        handle.block = Block::Running(block);
+        handle.restart_policy = restart_policy;
+        handle.started_at = Instant::now();
+        handle.update_render_cache()?;
+        self.schedule_render();
         Ok(())
     }
 
+    /// Stop a block and forget about it. The caller is responsible for removing the block from
+    /// the layout.
+    fn remove_block(&mut self, id: usize) {
+        if let Some(BlockHandle {
+            block: Block::Running(block),
+            ..
+        }) = self.blocks.remove(&id)
+        {
+            block.abort_handle.abort();
+        }
+        if self.fullscreen_block == Some(id) {
+            self.fullscreen_block = None;
+        }
+    }
+
+    /// Re-read the config file and apply it. Only new or modified blocks are (re)started, the rest
+    /// keep running and only get the updated theme and icons. Returns `true` if the set of config
+    /// files has changed, in which case they have to be watched again.
+    fn reload_config(&mut self) -> Result<bool> {
+        let (config, config_files) =
+            match config::load_config(&self.config_path, self.cli_args.profile.as_deref()) {
+                Ok(loaded) => loaded,
+                Err(error) => {
+                    // Keep the current state - the user is probably still editing the file
+                    eprintln!("Failed to reload config: {}", error);
+                    return Ok(false);
+                }
+            };
+        // Check the blocks before changing anything, so that a broken block doesn't leave the bar
+        // half-reloaded
+        let mut shared_configs = Vec::with_capacity(config.block.len());
+        for (block_type, block_config) in &config.block {
+            match CommonConfig::new(&mut block_config.clone())
+                .and_then(|common_config| common_config.shared_config(&config.shared))
+            {
+                Ok(shared_config) => shared_configs.push(shared_config),
+                Err(error) => {
+                    eprintln!("Failed to reload config: {:?}: {}", block_type, error);
+                    return Ok(false);
+                }
+            }
+        }
+
+        let running: Vec<_> = self
+            .layout
+            .iter()
+            .map(|id| (*id, self.blocks[id].block_type, &self.blocks[id].config))
+            .collect();
+        let unchanged = find_unchanged_blocks(&running, &config.block);
+        // The blocks which keep running must render with the new theme and icons
+        for (id, shared_config) in unchanged.iter().zip(&shared_configs) {
+            if let Some(id) = id {
+                if let Err(error) = self.blocks[id].check_shared_config(shared_config) {
+                    eprintln!("Failed to reload config: {}", error);
+                    return Ok(false);
+                }
+            }
+        }
+
+        self.shared_config = config.shared;
+        self.double_click_delay = Duration::from_millis(config.double_click_delay);
+        power::set_battery_factor(config.interval_on_battery);
+
+        let mut layout = Vec::with_capacity(config.block.len());
+        let blocks = config.block.into_iter().zip(unchanged).zip(shared_configs);
+        for (((block_type, block_config), unchanged), shared_config) in blocks {
+            let id = match unchanged {
+                Some(id) => {
+                    // Theme and icons might have changed
+                    let handle = self.blocks.get_mut(&id).unwrap();
+                    handle.set_shared_config(shared_config);
+                    if let Err(error) = handle.update_render_cache() {
+                        self.fail_block_on_reload(id, error);
+                    }
+                    id
+                }
+                None => match self.spawn_block(block_type, block_config) {
+                    Ok(id) => id,
+                    Err(error) => {
+                        // There is no block to show the error, and the bar keeps running
+                        eprintln!("Failed to reload config: {:?}: {}", block_type, error);
+                        continue;
+                    }
+                },
+            };
+            layout.push(id);
+        }
+        let removed: Vec<usize> = self
+            .layout
+            .iter()
+            .filter(|id| !layout.contains(id))
+            .copied()
+            .collect();
+        for id in removed {
+            self.remove_block(id);
+        }
+        self.layout = layout;
+
+        self.schedule_render();
+        let files_changed = config_files != self.config_files;
+        self.config_files = config_files;
+        Ok(files_changed)
+    }
+
+    /// Fail a block while applying a reload. A bad reload must never stop the bar.
+    fn fail_block_on_reload(&mut self, id: usize, error: Error) {
+        if let Err(error) = self.fail_block(id, error, true) {
+            eprintln!("Failed to reload config: {}", error);
+        }
+    }
+
     async fn process_request(&mut self, request: Request) -> Result<()> {
-        let (block, block_type) = self
-            .blocks
-            .get_mut(request.block_id)
-            .error("Message receiver: ID out of bounds")?;
-        let block = match block {
+        let handle = match self.blocks.get_mut(&request.block_id) {
+            Some(handle) => handle,
+            // Ignore requests from removed blocks
+            None => return Ok(()),
+        };
+        let block = match &mut handle.block {
             Block::Running(block) => block,
             Block::Failed(_) => {
-                // Ignore requests from failed blocks
-                return Ok(());
+                // Ignore requests from failed blocks, but keep the restart countdown up to date
+                return handle.update_render_cache();
             }
         };
+        // A lone `Noop` comes from a background task (e.g. a `rot-str` ticker), which keeps running
+        // when the block itself hangs
+        if !matches!(request.cmds.as_slice(), [RequestCmd::Noop]) {
+            block.last_update = Instant::now();
+            block.stale = false;
+        }
         for cmd in request.cmds {
             match cmd {
                 RequestCmd::Hide => block.hidden = true,
@@ -295,6 +848,20 @@ impl BarState {
                     block.event_sender = Some(sender);
                     let _ = tx.send(receiver);
                 }
+                RequestCmd::ListenMultiClicks(button, max_clicks) => {
+                    block.multi_clicks.insert(button, max_clicks);
+                }
+                RequestCmd::SetActions(actions) => {
+                    // Clicks bound to unknown actions are ignored, the block itself works fine
+                    if let Err(error) = block
+                        .click_handler
+                        .check_actions(actions)
+                        .in_block(handle.block_type, block.id)
+                    {
+                        eprintln!("{}", error);
+                    }
+                    block.actions = actions;
+                }
                 RequestCmd::SetIcon(icon) => block.widget.icon = icon,
                 RequestCmd::SetText(text) => block.widget.set_text(text),
                 RequestCmd::SetTexts(full, short) => block.widget.set_texts(full, short),
@@ -329,52 +896,319 @@ impl BarState {
                 }
                 RequestCmd::Preserve => block.widget.preserve(),
                 RequestCmd::Restore => block.widget.restore(),
-                RequestCmd::GetDbusConnection(tx) => match &self.dbus_connection {
-                    Some(conn) => {
-                        let _ = tx.send(Ok(conn.clone()));
-                    }
+                RequestCmd::GetDbusConnection(tx) => {
+                    let conn = Self::get_dbus_connection(
+                        &mut self.dbus_connection,
+                        &self.cli_args.dbus_name,
+                    )
+                    .await?;
+                    let _ = tx.send(Ok(conn));
+                }
+                RequestCmd::GetSystemDbusConnection(tx) => {
+                    let conn =
+                        Self::get_system_dbus_connection(&mut self.system_dbus_connection).await?;
+                    let _ = tx.send(Ok(conn));
+                }
+                RequestCmd::Noop => (),
+            }
+        }
+
+        handle.update_render_cache()
+    }
+
+    async fn get_dbus_connection(
+        dbus_connection: &mut Option<zbus::Connection>,
+        dbus_name: &str,
+    ) -> Result<zbus::Connection> {
+        match dbus_connection {
+            Some(conn) => Ok(conn.clone()),
+            None => {
+                let conn = util::new_dbus_connection().await?;
+                conn.request_name(dbus_name)
+                    .await
+                    .error("Failed to reuqest DBus name")?;
+                *dbus_connection = Some(conn.clone());
+                Ok(conn)
+            }
+        }
+    }
+
+    async fn get_system_dbus_connection(
+        system_dbus_connection: &mut Option<zbus::Connection>,
+    ) -> Result<zbus::Connection> {
+        match system_dbus_connection {
+            Some(conn) => Ok(conn.clone()),
+            None => {
+                let conn = util::new_system_dbus_connection().await?;
+                *system_dbus_connection = Some(conn.clone());
+                Ok(conn)
+            }
+        }
+    }
+
+    /// Listen for the system waking up from sleep
+    async fn watch_resume(&mut self) -> Result<mpsc::Receiver<()>> {
+        let conn = Self::get_system_dbus_connection(&mut self.system_dbus_connection).await?;
+        util::resume_stream(&conn).await
+    }
+
+    /// Track whether the system runs on battery
+    async fn watch_power_supply(&mut self) -> Result<()> {
+        let conn = Self::get_system_dbus_connection(&mut self.system_dbus_connection).await?;
+        power::watch_upower(&conn).await
+    }
+
+    /// Expose the control interface on the session bus
+    async fn start_control_interface(&mut self) -> Result<()> {
+        let conn =
+            Self::get_dbus_connection(&mut self.dbus_connection, &self.cli_args.dbus_name).await?;
+        control::serve(&conn, self.control_sender.clone()).await
+    }
+
+    async fn process_control_cmd(&mut self, cmd: ControlCmd) -> Result<()> {
+        match cmd {
+            ControlCmd::ListBlocks(tx) => {
+                let blocks = self
+                    .layout
+                    .iter()
+                    .map(|id| {
+                        let handle = &self.blocks[id];
+                        let status = match handle.block {
+                            Block::Running(_) => "running",
+                            Block::Failed(_) => "failed",
+                        };
+                        (
+                            *id as u32,
+                            format!("{:?}", handle.block_type),
+                            status.to_string(),
+                        )
+                    })
+                    .collect();
+                let _ = tx.send(blocks);
+            }
+            ControlCmd::SetHidden(id, hidden, tx) => {
+                let handle = match self.blocks.get_mut(&id) {
+                    Some(handle) => handle,
                     None => {
-                        let conn = util::new_dbus_connection().await?;
-                        conn.request_name(self.cli_args.dbus_name.as_str())
-                            .await
-                            .error("Failed to reuqest DBus name")?;
-                        self.dbus_connection = Some(conn.clone());
-                        let _ = tx.send(Ok(conn));
+                        let _ = tx.send(Err(Error::new("Unknown block ID")));
+                        return Ok(());
                     }
-                },
-                RequestCmd::GetSystemDbusConnection(tx) => match &self.system_dbus_connection {
-                    Some(conn) => {
-                        let _ = tx.send(Ok(conn.clone()));
+                };
+                handle.hidden_by_user = hidden;
+                handle.update_render_cache()?;
+                let _ = tx.send(Ok(()));
+                self.schedule_render();
+            }
+            ControlCmd::Update(id, tx) => {
+                let _ = tx.send(match self.blocks.get(&id).map(|h| &h.block) {
+                    Some(Block::Running(block)) => {
+                        block.update_request.notify_one();
+                        Ok(())
                     }
-                    None => {
-                        let conn = util::new_system_dbus_connection().await?;
-                        self.system_dbus_connection = Some(conn.clone());
-                        let _ = tx.send(Ok(conn));
+                    Some(Block::Failed(_)) => Err(Error::new("The block has failed")),
+                    None => Err(Error::new("Unknown block ID")),
+                });
+            }
+            ControlCmd::Click(event, tx) => {
+                if !self.blocks.contains_key(&event.id) {
+                    let _ = tx.send(Err(Error::new("Unknown block ID")));
+                    return Ok(());
+                }
+                let _ = tx.send(Ok(()));
+                self.dispatch_click(event).await?;
+            }
+            ControlCmd::ToggleFullScreen(id, tx) => {
+                if !self.blocks.contains_key(&id) {
+                    let _ = tx.send(Err(Error::new("Unknown block ID")));
+                    return Ok(());
+                }
+                self.fullscreen_block = match self.fullscreen_block {
+                    Some(fullscreen) if fullscreen == id => None,
+                    _ => Some(id),
+                };
+                let _ = tx.send(Ok(()));
+                self.schedule_render();
+            }
+            ControlCmd::Dump(tx) => {
+                let json = protocol::blocks_to_json(&self.displayed_blocks(), &self.shared_config);
+                let _ = tx.send(Ok(json.into()));
+            }
+        }
+        Ok(())
+    }
+
+    /// Combine consecutive clicks for blocks that are interested in multi-clicks, dispatch the
+    /// rest immediately
+    async fn handle_click(&mut self, mut event: I3BarEvent) -> Result<()> {
+        if let Some((pending, _)) = self.pending_click.take() {
+            if pending.same_click(&event) {
+                event.clicks = pending.clicks.saturating_add(1);
+            } else {
+                self.dispatch_click(pending).await?;
+            }
+        }
+        let max_clicks = match self.blocks.get(&event.id).map(|h| &h.block) {
+            Some(Block::Running(block)) => block.max_clicks(event.button),
+            _ => 1,
+        };
+        if event.clicks < max_clicks {
+            self.pending_click = Some((event, Instant::now() + self.double_click_delay));
+            Ok(())
+        } else {
+            self.dispatch_click(event).await
+        }
+    }
+
+    async fn dispatch_click(&mut self, event: I3BarEvent) -> Result<()> {
+        let handle = match self.blocks.get_mut(&event.id) {
+            Some(handle) => handle,
+            // The block was removed by a config reload
+            None => return Ok(()),
+        };
+        match &mut handle.block {
+            Block::Running(block) => {
+                let wants_event = event.clicks <= 1
+                    || block
+                        .multi_clicks
+                        .get(&event.button)
+                        .map_or(false, |&max_clicks| event.clicks <= max_clicks);
+                let forward = block
+                    .click_handler
+                    .handle(&event, block.widget.get_values())
+                    .await
+                    .in_block(handle.block_type, event.id)?
+                    && wants_event;
+                // An action bound in the config takes precedence over the block's own bindings
+                let action = match block.click_handler.action(&event) {
+                    Some(action) => Some(action),
+                    None if forward && event.clicks == 1 => block.default_action(event.button),
+                    None => None,
+                };
+                let event = match action {
+                    Some("refresh") => {
+                        block.update_request.notify_one();
+                        None
                     }
-                },
-                RequestCmd::Noop => (),
+                    // Unknown actions were reported when the block set its actions
+                    Some(action) => block.find_action(action).map(BlockEvent::Action),
+                    None if forward => Some(BlockEvent::Click(event)),
+                    None => None,
+                };
+                if let (Some(event), Some(sender)) = (event, &block.event_sender) {
+                    let _ = sender.send(event).await;
+                }
+            }
+            Block::Failed(block) => {
+                block.text = if self.fullscreen_block == Some(block.id) {
+                    self.fullscreen_block = None;
+                    block.error.message.as_deref().unwrap_or("Error").into()
+                } else {
+                    self.fullscreen_block = Some(block.id);
+                    block.error.to_string().into()
+                };
+                handle.update_render_cache()?;
+                self.schedule_render();
             }
         }
+        Ok(())
+    }
 
-        let data = &mut self.blocks_render_cache[block.id];
-        data.clear();
-        if !block.hidden {
-            data.push(block.widget.get_data().in_block(*block_type, block.id)?);
-            if !block.buttons_hidden {
-                for button in &block.buttons {
-                    data.push(button.get_data().in_block(*block_type, block.id)?);
+    /// Pause or resume the bar. Every block is updated on resume, since its info may be outdated.
+    fn set_paused(&mut self, paused: bool) {
+        if paused == self.paused {
+            return;
+        }
+        self.paused = paused;
+        let _ = BAR_PAUSED.0.send(paused);
+        if !paused {
+            for handle in self.blocks.values_mut() {
+                if let Block::Running(block) = &mut handle.block {
+                    // Blocks could not update while the bar was paused
+                    block.last_update = Instant::now();
+                    block.update_request.notify_one();
                 }
             }
+            self.schedule_render();
         }
+    }
 
+    /// The next time a running block becomes stale or times out
+    fn next_stale_deadline(&self) -> Option<Instant> {
+        self.blocks
+            .values()
+            .filter_map(|handle| match &handle.block {
+                Block::Running(block) => block.stale_deadline(),
+                Block::Failed(_) => None,
+            })
+            .min()
+    }
+
+    /// Mark blocks which have not updated for `stale_after` as stale, and fail the ones which have
+    /// not updated for `stale_timeout`, so they are restarted
+    fn check_stale_blocks(&mut self) -> Result<()> {
+        let now = Instant::now();
+        let mut changed = false;
+        for (&id, handle) in &mut self.blocks {
+            let block = match &mut handle.block {
+                Block::Running(block) => block,
+                Block::Failed(_) => continue,
+            };
+            let elapsed = now.saturating_duration_since(block.last_update);
+            if let Some(timeout) = block.stale_timeout {
+                if elapsed >= timeout {
+                    // Hung blocks are restarted even if they are not restarted on other errors
+                    if handle.restart_policy.restart == Restart::Never {
+                        handle.restart_policy.restart = Restart::OnError;
+                    }
+                    return Err(Error::new(format!(
+                        "Block has not updated for {}s",
+                        timeout.as_secs_f64()
+                    )))
+                    .in_block(handle.block_type, id);
+                }
+            }
+            if !block.stale && block.stale_after.map_or(false, |after| elapsed >= after) {
+                block.stale = true;
+                handle.update_render_cache()?;
+                changed = true;
+            }
+        }
+        if changed {
+            self.schedule_render();
+        }
         Ok(())
     }
 
-    fn render(&self) -> Result<()> {
-        if let Some(id) = self.fullscreen_block {
-            protocol::print_blocks(&[self.blocks_render_cache[id].clone()], &self.shared_config)
-        } else {
-            protocol::print_blocks(&self.blocks_render_cache, &self.shared_config)
+    /// Print the bar soon, together with any other changes that come in the meantime
+    fn schedule_render(&mut self) {
+        if self.render_deadline.is_none() {
+            self.render_deadline = Some(Instant::now() + RENDER_DELAY);
+        }
+    }
+
+    /// The widgets of the blocks that are currently displayed
+    fn displayed_blocks(&self) -> Vec<&[I3BarBlock]> {
+        match self.fullscreen_block.and_then(|id| self.blocks.get(&id)) {
+            Some(handle) => vec![handle.render_cache.as_slice()],
+            None => self
+                .layout
+                .iter()
+                .map(|id| self.blocks[id].render_cache.as_slice())
+                .collect(),
+        }
+    }
+
+    /// Print the bar if it has changed since the last time
+    fn render(&mut self) {
+        self.render_deadline = None;
+        let line = protocol::format_blocks(
+            &self.displayed_blocks(),
+            &self.shared_config,
+            self.cli_args.output,
+        );
+        if line != self.last_line {
+            println!("{}", line);
+            self.last_line = line;
         }
     }
 
@@ -382,53 +1216,109 @@ impl BarState {
         &mut self,
         signals_receiver: &mut mpsc::Receiver<Signal>,
         events_receiver: &mut mpsc::Receiver<I3BarEvent>,
+        config_updates_receiver: &mut mpsc::Receiver<()>,
+        resume_receiver: &mut mpsc::Receiver<()>,
     ) -> Result<()> {
+        let click_deadline = self.pending_click.map(|(_, deadline)| deadline);
+        let render_deadline = self.render_deadline;
+        let stale_deadline = self.next_stale_deadline();
+        let paused = self.paused;
         tokio::select! {
             // Handle blocks' errors
-            Some(block_result) = self.running_blocks.next() => {
-                block_result
+            Some((id, block_result)) = self.running_blocks.next() => match block_result {
+                Ok(Ok(())) => match self.blocks.get(&id) {
+                    // Blocks which are not restarted keep showing their last output
+                    Some(handle) if handle.restart_policy.restart == Restart::Always => {
+                        let error = Error {
+                            block: Some((handle.block_type, id)),
+                            ..Error::new("Block exited")
+                        };
+                        self.fail_block(id, error, false)
+                    }
+                    _ => Ok(()),
+                },
+                Ok(Err(error)) => Err(error),
+                // An aborted block was stopped on purpose
+                Err(Aborted) => Ok(()),
+            },
+            // Restart blocks
+            Some(id) = self.pending_restarts.next() => {
+                self.restart_block(id)
             }
             // Recieve messages from blocks
             Some(request) = self.request_receiver.recv() => {
                 self.process_request(request).await?;
-                self.render()
+                self.schedule_render();
+                Ok(())
             }
             // Handle clicks
             Some(event) = events_receiver.recv() => {
-                let (block, block_type) = self.blocks.get_mut(event.id).error("Events receiver: ID out of bounds")?;
-                match block {
-                    Block::Running(block) => {
-                        if block.click_handler.handle(event.button).await.in_block(*block_type, event.id)? {
-                            if let Some(sender) = &block.event_sender {
-                                let _ = sender.send(BlockEvent::Click(event)).await;
-                            }
-                        }
+                self.handle_click(event).await
+            }
+            // No more clicks came in time
+            _ = tokio::time::sleep_until(click_deadline.unwrap_or_else(Instant::now)),
+                if click_deadline.is_some() =>
+            {
+                match self.pending_click.take() {
+                    Some((event, _)) => self.dispatch_click(event).await,
+                    None => Ok(()),
+                }
+            }
+            // Print the changes of the last frame, unless the bar is paused and doesn't read them
+            _ = tokio::time::sleep_until(render_deadline.unwrap_or_else(Instant::now)),
+                if render_deadline.is_some() && !paused =>
+            {
+                self.render();
+                Ok(())
+            }
+            // Dim blocks which have not updated for a while and restart hung ones
+            _ = tokio::time::sleep_until(stale_deadline.unwrap_or_else(Instant::now)),
+                if stale_deadline.is_some() && !paused =>
+            {
+                self.check_stale_blocks()
+            }
+            // Handle commands from the control interface
+            Some(cmd) = self.control_receiver.recv() => {
+                self.process_control_cmd(cmd).await
+            }
+            // Handle config file changes
+            Some(()) = config_updates_receiver.recv() => {
+                if self.reload_config()? {
+                    // Includes were added or removed
+                    match config_updates_stream(&self.config_files) {
+                        Ok(receiver) => *config_updates_receiver = receiver,
+                        Err(error) => eprintln!("Failed to watch config files: {}", error),
                     }
-                    Block::Failed(block) => {
-                        let text = if self.fullscreen_block == Some(block.id) {
-                            self.fullscreen_block = None;
-                            block.error.message.as_deref().unwrap_or("Error").into()
-                        } else {
-                            self.fullscreen_block = Some(block.id);
-                            block.error.to_string().into()
-                        };
-                        block.error_widget.set_text(text);
-                        let data = &mut self.blocks_render_cache[block.id];
-                        data.clear();
-                        data.push(block.error_widget.get_data()?);
-                        self.render()?;
+                }
+                Ok(())
+            }
+            // The info of most blocks is outdated after the system was asleep
+            Some(()) = resume_receiver.recv() => {
+                for handle in self.blocks.values() {
+                    if let Block::Running(block) = &handle.block {
+                        if block.refresh_on_resume {
+                            block.update_request.notify_one();
+                        }
                     }
                 }
                 Ok(())
             }
-            // Handle signals
+            // Handle signals: USR1 refreshes every block, other signals only the matching ones
             Some(signal) = signals_receiver.recv() => match signal {
                 Signal::Usr2 => restart(),
+                Signal::Stop => {
+                    self.set_paused(true);
+                    Ok(())
+                }
+                Signal::Cont => {
+                    self.set_paused(false);
+                    Ok(())
+                }
                 signal => {
-                    for (block, _) in &self.blocks {
-                        if let Block::Running(block) = block {
-                            if let Some(sender) = &block.event_sender {
-                                let _ = sender.send(BlockEvent::Signal(signal)).await;
+                    for handle in self.blocks.values() {
+                        if let Block::Running(block) = &handle.block {
+                            if signal == Signal::Usr1 || block.signal == Some(signal) {
+                                block.update_request.notify_one();
                             }
                         }
                     }
@@ -442,27 +1332,40 @@ impl BarState {
         mut self,
         signals_receiver: &mut mpsc::Receiver<Signal>,
         events_receiver: &mut mpsc::Receiver<I3BarEvent>,
+        config_updates_receiver: &mut mpsc::Receiver<()>,
     ) -> Result<()> {
+        if self.cli_args.control {
+            if let Err(error) = self.start_control_interface().await {
+                // The bar is usable without the control interface
+                eprintln!("{}", error);
+            }
+        }
+        let mut resume_receiver = match self.watch_resume().await {
+            Ok(receiver) => receiver,
+            Err(error) => {
+                // Blocks are still refreshed by their intervals
+                eprintln!("{}", error);
+                mpsc::channel(1).1
+            }
+        };
+        if self.watch_power_supply().await.is_err() {
+            // UPower is not available
+            power::poll_power_supply();
+        }
+
         loop {
-            if let Err(error) = self.process_event(signals_receiver, events_receiver).await {
+            if let Err(error) = self
+                .process_event(
+                    signals_receiver,
+                    events_receiver,
+                    config_updates_receiver,
+                    &mut resume_receiver,
+                )
+                .await
+            {
                 match error.block {
                     Some((_, id)) => {
-                        let block = FailedBlock {
-                            id,
-                            error_widget: Widget::new(id, self.shared_config.clone())
-                                .with_state(State::Critical)
-                                .with_text(error.message.as_deref().unwrap_or("Error").into()),
-                            error,
-                        };
-
-                        let data = &mut self.blocks_render_cache[block.id];
-                        data.clear();
-                        data.push(block.error_widget.get_data()?);
-
-                        self.render()?;
-
-                        self.blocks[id].0 = Block::Failed(block);
-                        self.fullscreen_block = None;
+                        self.fail_block(id, error, true)?;
                     }
                     None => return Err(error),
                 }
@@ -471,6 +1374,51 @@ impl BarState {
     }
 }
 
+/// For every block of a new config, find a running block of the same type and with the same
+/// config, which can be kept instead of starting a new one. Every running block is kept at most once.
+fn find_unchanged_blocks(
+    running: &[(usize, BlockType, &toml::Value)],
+    blocks: &[(BlockType, toml::Value)],
+) -> Vec<Option<usize>> {
+    let mut running = running.to_vec();
+    blocks
+        .iter()
+        .map(|(block_type, config)| {
+            let pos = running
+                .iter()
+                .position(|(_, t, c)| t == block_type && *c == config)?;
+            Some(running.remove(pos).0)
+        })
+        .collect()
+}
+
+fn find_config(config: &str) -> Result<PathBuf> {
+    util::find_file(config, None, Some("toml"))
+        .or_error(|| format!("Configuration file '{}' not found", config))
+}
+
+/// Check the config file without starting the bar. Returns all found problems.
+fn check_config(config: &str, profile: Option<&str>) -> StdResult<(), Vec<Error>> {
+    let (config, _) = find_config(config)
+        .and_then(|path| config::load_config(&path, profile))
+        .config_error()
+        .map_err(|e| vec![e])?;
+
+    let errors: Vec<Error> = config
+        .block
+        .into_iter()
+        .enumerate()
+        .flat_map(|(id, (block_type, block_config))| {
+            block_type.check(block_config, &config.shared, id)
+        })
+        .collect();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
 /// Restart `swaystatus` in-place
 fn restart() -> ! {
     use std::env;
@@ -493,3 +1441,150 @@ fn restart() -> ! {
     nix::unistd::execvp(&exe, &arg).unwrap();
     unreachable!();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn block(block_type: BlockType, config: &str) -> (BlockType, toml::Value) {
+        (block_type, toml::from_str(config).unwrap())
+    }
+
+    #[test]
+    fn test_find_unchanged_blocks() {
+        let time = block(BlockType::time, "interval = 5");
+        let time_changed = block(BlockType::time, "interval = 10");
+        let cpu = block(BlockType::cpu, "interval = 5");
+        let running = [
+            (0, time.0, &time.1),
+            (1, cpu.0, &cpu.1),
+            (2, time.0, &time.1),
+        ];
+
+        // Unchanged blocks are kept, even if they were moved
+        assert_eq!(
+            find_unchanged_blocks(&running, &[cpu.clone(), time.clone()]),
+            [Some(1), Some(0)]
+        );
+        // Identical blocks are kept once each
+        assert_eq!(
+            find_unchanged_blocks(&running, &[time.clone(), time.clone(), time.clone()]),
+            [Some(0), Some(2), None]
+        );
+        // A block with a changed config or type is started again
+        assert_eq!(
+            find_unchanged_blocks(&running, &[time_changed, block(BlockType::cpu, "")]),
+            [None, None]
+        );
+    }
+
+    #[test]
+    fn test_check_format() {
+        let shared_config = SharedConfig::default();
+        let (block_type, config) = block(BlockType::focused_window, r#"format = "$title.foo()""#);
+        let errors = block_type.check(config, &shared_config, 0);
+        assert_eq!(errors.len(), 1);
+        assert!(
+            errors[0].to_string().contains(" at 1:8 of "),
+            "{}",
+            errors[0]
+        );
+        assert!(!errors[0].to_string().contains('\n'));
+
+        let (block_type, config) = block(BlockType::focused_window, r#"format = "$title|""#);
+        assert!(block_type.check(config, &shared_config, 0).is_empty());
+    }
+
+    #[test]
+    fn test_check_collects_all_errors() {
+        let shared_config = SharedConfig::default();
+        let (block_type, config) = block(
+            BlockType::cpu,
+            r#"
+            format = "$utilization.foo()"
+            format_alt = "{$utilization"
+            click = [{ button = "left", action = "nope" }]
+            "#,
+        );
+        let errors = block_type.check(config, &shared_config, 3);
+        assert_eq!(errors.len(), 3, "{:?}", errors);
+        assert!(errors.iter().all(|e| e.block == Some((BlockType::cpu, 3))));
+
+        // A config which can not be deserialized can not be checked any further
+        let (block_type, config) = block(BlockType::cpu, "format = 1\nfoo = 2");
+        assert_eq!(block_type.check(config, &shared_config, 0).len(), 1);
+    }
+
+    #[test]
+    fn test_check_icons() {
+        // The icons of the default configs are in the "none" icon set
+        let shared_config = SharedConfig::default();
+        for block_type in [
+            BlockType::battery,
+            BlockType::cpu,
+            BlockType::disk_space,
+            BlockType::kdeconnect,
+            BlockType::load,
+            BlockType::memory,
+            BlockType::net,
+            BlockType::sound,
+            BlockType::speedtest,
+            BlockType::time,
+            BlockType::uptime,
+            BlockType::xrandr,
+        ] {
+            let (block_type, config) = block(block_type, "");
+            let errors = block_type.check(config, &shared_config, 0);
+            assert!(errors.is_empty(), "{:?}: {:?}", block_type, errors);
+        }
+
+        // Every missing icon is reported, including the ones picked at runtime
+        let shared_config = SharedConfig {
+            icons: Arc::new(crate::icons::Icons(HashMap::new())),
+            ..SharedConfig::default()
+        };
+        let (block_type, config) = block(BlockType::sound, "");
+        assert_eq!(block_type.check(config, &shared_config, 0).len(), 4);
+        let (block_type, config) = block(
+            BlockType::sound,
+            "device_kind = \"source\"\nheadphones_indicator = true",
+        );
+        assert_eq!(block_type.check(config, &shared_config, 0).len(), 4);
+        let (block_type, config) = block(BlockType::sound, "headphones_indicator = true");
+        assert_eq!(block_type.check(config, &shared_config, 0).len(), 5);
+    }
+
+    #[test]
+    fn test_check_config() {
+        let dir = std::env::temp_dir().join(format!("swaystatus-check-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("config.toml");
+        std::fs::write(
+            &path,
+            r#"
+            [[block]]
+            block = "time"
+
+            [[block]]
+            block = "cpu"
+            format = "$utilization.foo()"
+
+            [[block]]
+            block = "load"
+            nope = 1
+            "#,
+        )
+        .unwrap();
+
+        let errors = check_config(path.to_str().unwrap(), None).unwrap_err();
+        assert_eq!(
+            errors.iter().map(|e| e.block).collect::<Vec<_>>(),
+            [Some((BlockType::cpu, 1)), Some((BlockType::load, 2))]
+        );
+
+        std::fs::write(&path, "[[block]]\nblock = \"time\"\n").unwrap();
+        assert!(check_config(path.to_str().unwrap(), None).is_ok());
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
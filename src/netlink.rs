@@ -11,6 +11,13 @@ use std::path::{Path, PathBuf};
 use crate::errors::*;
 use crate::util;
 
+pub const ICON_WIRELESS: &str = "net_wireless";
+pub const ICON_VPN: &str = "net_vpn";
+pub const ICON_LOOPBACK: &str = "net_loopback";
+pub const ICON_WIRED: &str = "net_wired";
+/// The icons a [`NetDevice`] can have
+pub const DEVICE_ICONS: [&str; 4] = [ICON_WIRELESS, ICON_VPN, ICON_LOOPBACK, ICON_WIRED];
+
 #[derive(Debug)]
 pub struct NetDevice {
     pub interface: String,
@@ -42,13 +49,13 @@ impl NetDevice {
             .unwrap_or((false, false));
 
         let icon = if wireless {
-            "net_wireless"
+            ICON_WIRELESS
         } else if tun || wg || ppp {
-            "net_vpn"
+            ICON_VPN
         } else if interface == "lo" {
-            "net_loopback"
+            ICON_LOOPBACK
         } else {
-            "net_wired"
+            ICON_WIRED
         };
 
         NetDevice {
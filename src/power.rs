@@ -0,0 +1,260 @@
+//! Tracking of the power supply state, used to slow down block updates while on battery
+
+use futures::future::Future;
+use futures::stream::StreamExt;
+use once_cell::sync::Lazy;
+use std::path::Path;
+use std::time::Duration;
+use tokio::sync::watch;
+use tokio::time::{sleep_until, Instant};
+
+use crate::errors::*;
+use crate::util::read_file;
+
+const POWER_SUPPLY_DEVICES_PATH: &str = "/sys/class/power_supply";
+
+/// How often `/sys/class/power_supply` is checked when UPower is not available
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct PowerState {
+    on_battery: bool,
+    /// The global `interval_on_battery` multiplier
+    battery_factor: f64,
+}
+
+static POWER_STATE: Lazy<(watch::Sender<PowerState>, watch::Receiver<PowerState>)> =
+    Lazy::new(|| {
+        watch::channel(PowerState {
+            on_battery: false,
+            battery_factor: 1.0,
+        })
+    });
+
+tokio::task_local! {
+    /// Block's own `interval_on_battery`, overriding the global one
+    static BLOCK_BATTERY_FACTOR: Option<f64>;
+}
+
+#[zbus::dbus_proxy(
+    interface = "org.freedesktop.UPower",
+    default_service = "org.freedesktop.UPower",
+    default_path = "/org/freedesktop/UPower"
+)]
+trait UPower {
+    #[dbus_proxy(property)]
+    fn on_battery(&self) -> zbus::Result<bool>;
+}
+
+fn update_state(f: impl FnOnce(&mut PowerState)) {
+    let mut state = *POWER_STATE.1.borrow();
+    f(&mut state);
+    // Avoid waking up all the timers if nothing has changed
+    if state != *POWER_STATE.1.borrow() {
+        let _ = POWER_STATE.0.send(state);
+    }
+}
+
+fn set_on_battery(on_battery: bool) {
+    update_state(|state| state.on_battery = on_battery);
+}
+
+/// Check an `interval_on_battery` multiplier
+pub fn check_battery_factor(battery_factor: f64) -> Result<()> {
+    if battery_factor.is_finite() && battery_factor > 0.0 {
+        Ok(())
+    } else {
+        Err(Error::new(
+            "'interval_on_battery' must be a positive number",
+        ))
+    }
+}
+
+/// Set the global multiplier of update intervals while on battery
+pub fn set_battery_factor(battery_factor: f64) {
+    update_state(|state| state.battery_factor = battery_factor);
+}
+
+/// Run a block's future with its own `interval_on_battery`, if any
+pub fn with_battery_factor<F: Future>(
+    battery_factor: Option<f64>,
+    future: F,
+) -> impl Future<Output = F::Output> {
+    BLOCK_BATTERY_FACTOR.scope(battery_factor, future)
+}
+
+fn scaled(period: Duration, state: PowerState) -> Duration {
+    if state.on_battery {
+        let factor = BLOCK_BATTERY_FACTOR
+            .try_with(|factor| *factor)
+            .ok()
+            .flatten()
+            .unwrap_or(state.battery_factor);
+        period.mul_f64(factor)
+    } else {
+        period
+    }
+}
+
+/// Sleep until `period` has passed since `since`. The period is scaled by the `interval_on_battery`
+/// multiplier while on battery, and adjusted if the power supply changes while sleeping.
+pub async fn sleep_since(since: Instant, period: Duration) {
+    let mut state = POWER_STATE.1.clone();
+    loop {
+        let deadline = since + scaled(period, *state.borrow());
+        tokio::select! {
+            _ = sleep_until(deadline) => return,
+            Ok(()) = state.changed() => (),
+        }
+    }
+}
+
+/// Follow UPower's `OnBattery` property
+pub async fn watch_upower(conn: &zbus::Connection) -> Result<()> {
+    let proxy = UPowerProxy::new(conn)
+        .await
+        .error("Failed to create UPowerProxy")?;
+    set_on_battery(
+        proxy
+            .on_battery()
+            .await
+            .error("Failed to get OnBattery property")?,
+    );
+    let mut changes = proxy.receive_on_battery_changed().await;
+    tokio::spawn(async move {
+        while changes.next().await.is_some() {
+            // zbus does internal caching
+            if let Ok(on_battery) = proxy.on_battery().await {
+                set_on_battery(on_battery);
+            }
+        }
+    });
+    Ok(())
+}
+
+/// Periodically check the AC adapters in `/sys/class/power_supply`
+pub fn poll_power_supply() {
+    tokio::spawn(async {
+        loop {
+            set_on_battery(read_on_battery(Path::new(POWER_SUPPLY_DEVICES_PATH)).await);
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    });
+}
+
+/// The system is on battery if it has AC adapters and none of them is online
+async fn read_on_battery(devices_path: &Path) -> bool {
+    let mut devices = match tokio::fs::read_dir(devices_path).await {
+        Ok(devices) => devices,
+        Err(_) => return false,
+    };
+    let mut has_mains = false;
+    while let Ok(Some(device)) = devices.next_entry().await {
+        let path = device.path();
+        if read_prop(&path, "type").await.as_deref() == Some("Mains") {
+            has_mains = true;
+            if read_prop(&path, "online").await.as_deref() == Some("1") {
+                return false;
+            }
+        }
+    }
+    has_mains
+}
+
+async fn read_prop(device: &Path, prop: &str) -> Option<String> {
+    read_file(&device.join(prop)).await.ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::path::PathBuf;
+
+    fn state(on_battery: bool) -> PowerState {
+        PowerState {
+            on_battery,
+            battery_factor: 3.0,
+        }
+    }
+
+    #[test]
+    fn test_scaled_on_ac() {
+        let period = Duration::from_secs(10);
+        assert_eq!(scaled(period, state(false)), period);
+        let block_scaled = with_battery_factor(Some(2.0), async { scaled(period, state(false)) });
+        assert_eq!(tokio_test::block_on(block_scaled), period);
+    }
+
+    #[test]
+    fn test_scaled_on_battery() {
+        let period = Duration::from_secs(10);
+        assert_eq!(scaled(period, state(true)), Duration::from_secs(30));
+        let block_scaled = with_battery_factor(Some(0.5), async { scaled(period, state(true)) });
+        assert_eq!(tokio_test::block_on(block_scaled), Duration::from_secs(5));
+        let global_scaled = with_battery_factor(None, async { scaled(period, state(true)) });
+        assert_eq!(tokio_test::block_on(global_scaled), Duration::from_secs(30));
+    }
+
+    #[test]
+    fn test_check_battery_factor() {
+        assert!(check_battery_factor(0.5).is_ok());
+        assert!(check_battery_factor(0.0).is_err());
+        assert!(check_battery_factor(-1.0).is_err());
+        assert!(check_battery_factor(f64::NAN).is_err());
+        assert!(check_battery_factor(f64::INFINITY).is_err());
+    }
+
+    /// Devices with their `type` and `online` properties
+    type Devices<'a> = &'a [(&'a str, &'a str, &'a str)];
+
+    /// Create a fake `/sys/class/power_supply` with the given devices
+    fn power_supply_dir(name: &str, devices: Devices) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("swaystatus-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        for (device, device_type, online) in devices {
+            let path = dir.join(device);
+            fs::create_dir_all(&path).unwrap();
+            fs::write(path.join("type"), format!("{}\n", device_type)).unwrap();
+            fs::write(path.join("online"), format!("{}\n", online)).unwrap();
+        }
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_read_on_battery() {
+        let cases: &[(&str, Devices, bool)] = &[
+            ("none", &[], false),
+            ("no-mains", &[("BAT0", "Battery", "1")], false),
+            (
+                "offline",
+                &[("AC", "Mains", "0"), ("BAT0", "Battery", "1")],
+                true,
+            ),
+            (
+                "online",
+                &[("AC", "Mains", "1"), ("BAT0", "Battery", "1")],
+                false,
+            ),
+            (
+                "one-online",
+                &[("AC0", "Mains", "0"), ("AC1", "Mains", "1")],
+                false,
+            ),
+        ];
+        for (name, devices, on_battery) in cases {
+            let dir = power_supply_dir(name, devices);
+            assert_eq!(
+                tokio_test::block_on(read_on_battery(&dir)),
+                *on_battery,
+                "{}",
+                name
+            );
+            fs::remove_dir_all(&dir).unwrap();
+        }
+        assert!(!tokio_test::block_on(read_on_battery(Path::new(
+            "/nonexistent/power_supply"
+        ))));
+    }
+}
@@ -1,24 +1,137 @@
 pub mod i3bar_block;
 pub mod i3bar_event;
 
+use clap::ArgEnum;
+use serde_derive::Serialize;
+use serde_json::json;
+
 use crate::config::SharedConfig;
-use crate::errors::*;
+use crate::escape::strip_pango;
+use crate::signals::{CONT_SIGNAL, STOP_SIGNAL};
 use crate::themes::Color;
 
 use i3bar_block::I3BarBlock;
 
-pub fn init(never_pause: bool) {
+/// Text put between blocks by the text based outputs when the theme has no separator
+const NATIVE_SEPARATOR: &str = " | ";
+
+/// The format in which the bar is printed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ArgEnum)]
+pub enum Output {
+    /// The i3bar protocol, understood by i3bar and swaybar
+    I3bar,
+    /// Plain text lines without any markup, e.g. for terminal status lines
+    Plain,
+    /// Text with tmux style attributes (`#[fg=..,bg=..]`)
+    Tmux,
+    /// Text with lemonbar formatting blocks (`%{F..}`)
+    Lemonbar,
+    /// JSON lines for a Waybar custom module
+    Waybar,
+}
+
+impl Output {
+    /// Whether the bar sends click events to stdin
+    pub fn click_events(self) -> bool {
+        self == Self::I3bar
+    }
+}
+
+pub fn init(output: Output, never_pause: bool) {
+    if output != Output::I3bar {
+        return;
+    }
     if never_pause {
         println!("{{\"version\": 1, \"click_events\": true, \"stop_signal\": 0}}\n[");
     } else {
-        println!("{{\"version\": 1, \"click_events\": true}}\n[");
+        // Ask for signals we can handle instead of being stopped with SIGSTOP
+        println!(
+            "{{\"version\": 1, \"click_events\": true, \"stop_signal\": {}, \"cont_signal\": {}}}\n[",
+            STOP_SIGNAL, CONT_SIGNAL
+        );
+    }
+}
+
+/// The theme's separator as a widget. Its colors are set by `render_blocks`.
+fn theme_separator(config: &SharedConfig) -> Option<I3BarBlock> {
+    config.theme.separator.as_ref().map(|separator| I3BarBlock {
+        full_text: separator.clone().into(),
+        ..Default::default()
+    })
+}
+
+/// The bar as a JSON array of i3bar blocks, the way i3bar gets it
+pub fn blocks_to_json(blocks: &[&[I3BarBlock]], config: &SharedConfig) -> String {
+    let separator = theme_separator(config);
+    serde_json::to_string(&render_blocks(blocks, config, separator.as_ref())).unwrap()
+}
+
+/// Format the bar as a single line (without the trailing newline)
+pub fn format_blocks(blocks: &[&[I3BarBlock]], config: &SharedConfig, output: Output) -> String {
+    let separator = theme_separator(config);
+    let rendered_blocks = render_blocks(blocks, config, separator.as_ref());
+    match output {
+        Output::I3bar => format!("{},", serde_json::to_string(&rendered_blocks).unwrap()),
+        Output::Plain => render_text(&rendered_blocks, |text, _| text),
+        Output::Tmux => render_text(&rendered_blocks, tmux_style),
+        Output::Lemonbar => render_text(&rendered_blocks, lemonbar_style),
+        Output::Waybar => {
+            let mut text = String::new();
+            for widget in &rendered_blocks {
+                text.push_str(&waybar_span(widget));
+                if widget.separator.is_none() {
+                    text.push_str(NATIVE_SEPARATOR);
+                }
+            }
+            text.truncate(text.trim_end_matches(NATIVE_SEPARATOR).len());
+            let mut module = json!({ "text": text });
+            if rendered_blocks.iter().any(|w| w.data.urgent == Some(true)) {
+                module["class"] = "urgent".into();
+            }
+            module.to_string()
+        }
+    }
+}
+
+/// A widget as it is printed. Borrows the widget's data and only overrides what the theme changes,
+/// so rendering doesn't clone the widgets.
+#[derive(Serialize)]
+struct RenderedWidget<'a> {
+    #[serde(flatten)]
+    data: &'a I3BarBlock,
+    #[serde(skip_serializing_if = "Color::skip_ser")]
+    color: Color,
+    #[serde(skip_serializing_if = "Color::skip_ser")]
+    background: Color,
+    /// `None` requests a native separator after this widget
+    #[serde(skip_serializing_if = "Option::is_none")]
+    separator: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    separator_block_width: Option<usize>,
+}
+
+impl<'a> RenderedWidget<'a> {
+    fn new(data: &'a I3BarBlock) -> Self {
+        Self {
+            data,
+            color: data.color,
+            background: data.background,
+            separator: data.separator,
+            separator_block_width: data.separator_block_width,
+        }
     }
 }
 
-pub fn print_blocks(blocks: &[Vec<I3BarBlock>], config: &SharedConfig) -> Result<()> {
+/// Apply the alternating tint and add the theme's separators. `separator` is the theme's
+/// separator widget, its colors are set here.
+fn render_blocks<'a>(
+    blocks: &[&'a [I3BarBlock]],
+    config: &SharedConfig,
+    separator: Option<&'a I3BarBlock>,
+) -> Vec<RenderedWidget<'a>> {
     let mut last_bg = Color::None;
 
-    let mut rendered_blocks = vec![];
+    let mut rendered_blocks = Vec::with_capacity(blocks.iter().map(|b| b.len() + 1).sum());
 
     // The right most block should never be alternated
     let mut alt = true;
@@ -33,22 +146,27 @@ pub fn print_blocks(blocks: &[Vec<I3BarBlock>], config: &SharedConfig) -> Result
             continue;
         }
 
-        let mut rendered_widgets = widgets.clone();
+        if let Some(separator) = separator {
+            // The colors are set once the widgets are tinted
+            rendered_blocks.push(RenderedWidget::new(separator));
+        }
+        let first = rendered_blocks.len();
+        rendered_blocks.extend(widgets.iter().map(RenderedWidget::new));
 
         // Apply tint for all widgets of every second block
         // TODO: Allow for other non-additive tints
         if alt {
-            for data in &mut rendered_widgets {
-                data.background = data.background + config.theme.alternating_tint_bg;
-                data.color = data.color + config.theme.alternating_tint_fg;
+            for widget in &mut rendered_blocks[first..] {
+                widget.background = widget.background + config.theme.alternating_tint_bg;
+                widget.color = widget.color + config.theme.alternating_tint_fg;
             }
         }
         alt = !alt;
 
-        if let Some(separator) = &config.theme.separator {
+        if separator.is_some() {
             // The first widget's BG is used to get the FG color for the current separator
             let sep_fg = if config.theme.separator_fg == Color::Auto {
-                rendered_widgets.first().unwrap().background
+                rendered_blocks[first].background
             } else {
                 config.theme.separator_fg
             };
@@ -61,27 +179,181 @@ pub fn print_blocks(blocks: &[Vec<I3BarBlock>], config: &SharedConfig) -> Result
             };
 
             // The last widget's BG is used to get the BG color for the next separator
-            last_bg = rendered_widgets.last().unwrap().background;
-
-            let separator = I3BarBlock {
-                full_text: separator.clone().into(),
-                background: sep_bg,
-                color: sep_fg,
-                ..Default::default()
-            };
+            last_bg = rendered_blocks.last().unwrap().background;
 
-            rendered_blocks.push(separator);
-            rendered_blocks.extend(rendered_widgets);
+            let separator = &mut rendered_blocks[first - 1];
+            separator.color = sep_fg;
+            separator.background = sep_bg;
         } else {
             // Re-add native separator on last widget for native theme
-            rendered_widgets.last_mut().unwrap().separator = None;
-            rendered_widgets.last_mut().unwrap().separator_block_width = None;
+            let last = rendered_blocks.last_mut().unwrap();
+            last.separator = None;
+            last.separator_block_width = None;
+        }
+    }
+
+    rendered_blocks
+}
 
-            rendered_blocks.extend(rendered_widgets);
+/// Join the widgets' text with the pango markup stripped, styling each widget with `style`
+fn render_text(widgets: &[RenderedWidget], style: fn(String, &RenderedWidget) -> String) -> String {
+    let mut line = String::new();
+    for widget in widgets {
+        let text = strip_pango(&widget.data.full_text);
+        if !text.is_empty() {
+            line.push_str(&style(text, widget));
+        }
+        // A native separator is requested after the last widget of a block
+        if widget.separator.is_none() {
+            line.push_str(NATIVE_SEPARATOR);
+        }
+    }
+    line.truncate(line.trim_end_matches(NATIVE_SEPARATOR).len());
+    line
+}
+
+fn tmux_style(text: String, widget: &RenderedWidget) -> String {
+    let text = text.replace('#', "##");
+    let mut attrs = Vec::new();
+    if let Some((r, g, b, _)) = widget.color.rgba() {
+        attrs.push(format!("fg=#{:02x}{:02x}{:02x}", r, g, b));
+    }
+    if let Some((r, g, b, _)) = widget.background.rgba() {
+        attrs.push(format!("bg=#{:02x}{:02x}{:02x}", r, g, b));
+    }
+    if attrs.is_empty() {
+        text
+    } else {
+        format!("#[{}]{}#[default]", attrs.join(","), text)
+    }
+}
+
+fn lemonbar_style(text: String, widget: &RenderedWidget) -> String {
+    let mut styled = text.replace('%', "%%");
+    if let Some((r, g, b, a)) = widget.color.rgba() {
+        styled = format!(
+            "%{{F#{:02X}{:02X}{:02X}{:02X}}}{}%{{F-}}",
+            a, r, g, b, styled
+        );
+    }
+    if let Some((r, g, b, a)) = widget.background.rgba() {
+        styled = format!(
+            "%{{B#{:02X}{:02X}{:02X}{:02X}}}{}%{{B-}}",
+            a, r, g, b, styled
+        );
+    }
+    styled
+}
+
+/// Wrap the widget's pango markup in a span with its colors
+fn waybar_span(widget: &RenderedWidget) -> String {
+    let mut attrs = String::new();
+    if let Some((r, g, b, a)) = widget.color.rgba() {
+        attrs.push_str(&format!(
+            " foreground=\"#{:02X}{:02X}{:02X}{:02X}\"",
+            r, g, b, a
+        ));
+    }
+    if let Some((r, g, b, a)) = widget.background.rgba() {
+        attrs.push_str(&format!(
+            " background=\"#{:02X}{:02X}{:02X}{:02X}\"",
+            r, g, b, a
+        ));
+    }
+    format!("<span{}>{}</span>", attrs, widget.data.full_text)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::themes::Theme;
+    use std::sync::Arc;
+
+    fn widget(text: &str, color: &str, background: &str) -> I3BarBlock {
+        I3BarBlock {
+            full_text: text.into(),
+            color: color.parse().unwrap(),
+            background: background.parse().unwrap(),
+            ..Default::default()
+        }
+    }
+
+    fn with_separator(separator: &str) -> SharedConfig {
+        SharedConfig {
+            theme: Arc::new(Theme {
+                separator: Some(separator.into()),
+                ..Default::default()
+            }),
+            ..Default::default()
         }
     }
 
-    println!("{},", serde_json::to_string(&rendered_blocks).unwrap());
+    #[test]
+    fn plain() {
+        let config = SharedConfig::default();
+        let first = [widget("<b>a</b> &amp; b", "#ff0000", "")];
+        let second = [widget("c", "", ""), widget("", "", ""), widget("d", "", "")];
+        let blocks: &[&[I3BarBlock]] = &[&first, &[], &second];
+        assert_eq!(format_blocks(blocks, &config, Output::Plain), "a & b | cd");
+        assert_eq!(
+            format_blocks(blocks, &with_separator("/"), Output::Plain),
+            "/a & b/cd"
+        );
+    }
+
+    #[test]
+    fn tmux() {
+        let config = SharedConfig::default();
+        let blocks: &[&[I3BarBlock]] = &[
+            &[widget("a#b", "#ff0000", "")],
+            &[widget("c", "", "#00ff00")],
+            &[widget("d", "#0000ff", "#ffffff")],
+        ];
+        assert_eq!(
+            format_blocks(blocks, &config, Output::Tmux),
+            "#[fg=#ff0000]a##b#[default] | #[bg=#00ff00]c#[default] \
+             | #[fg=#0000ff,bg=#ffffff]d#[default]"
+        );
+    }
+
+    #[test]
+    fn lemonbar() {
+        let config = SharedConfig::default();
+        let blocks: &[&[I3BarBlock]] = &[
+            &[widget("50%", "#ff000080", "#0000ff")],
+            &[widget("x", "", "")],
+        ];
+        assert_eq!(
+            format_blocks(blocks, &config, Output::Lemonbar),
+            "%{B#FF0000FF}%{F#80FF0000}50%%%{F-}%{B-} | x"
+        );
+    }
+
+    #[test]
+    fn waybar() {
+        let config = SharedConfig::default();
+        let urgent = I3BarBlock {
+            urgent: Some(true),
+            ..widget("b &amp; c", "", "")
+        };
+        let blocks: &[&[I3BarBlock]] = &[&[widget("<b>a</b>", "#ff0000", "")], &[urgent]];
+        assert_eq!(
+            format_blocks(blocks, &config, Output::Waybar),
+            r##"{"class":"urgent","text":"<span foreground=\"#FF0000FF\"><b>a</b></span> | <span>b &amp; c</span>"}"##
+        );
+        assert_eq!(
+            format_blocks(&blocks[..1], &with_separator("/"), Output::Waybar),
+            r##"{"text":"<span>/</span><span foreground=\"#FF0000FF\"><b>a</b></span>"}"##
+        );
+    }
 
-    Ok(())
+    #[test]
+    fn i3bar() {
+        let config = SharedConfig::default();
+        let blocks: &[&[I3BarBlock]] = &[&[widget("a", "#ff0000", ""), widget("b", "", "")]];
+        assert_eq!(
+            format_blocks(blocks, &config, Output::I3bar),
+            r##"[{"full_text":"a","markup":"pango","color":"#FF0000FF","separator":false,"separator_block_width":0},{"full_text":"b","markup":"pango"}],"##
+        );
+    }
 }
@@ -3,14 +3,17 @@ use crate::themes::Color;
 use serde_derive::Serialize;
 
 /// Represent block as described in <https://i3wm.org/docs/i3bar-protocol.html>
+///
+/// The colors and the separator are serialized by the rendered widget in `protocol`, which
+/// overrides them, so serialize blocks through `protocol::blocks_to_json`.
 #[derive(Serialize, Debug, Clone)]
 pub struct I3BarBlock {
     pub full_text: String,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub short_text: Option<String>,
-    #[serde(skip_serializing_if = "Color::skip_ser")]
+    #[serde(skip)]
     pub color: Color,
-    #[serde(skip_serializing_if = "Color::skip_ser")]
+    #[serde(skip)]
     pub background: Color,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub border: Option<String>,
@@ -32,9 +35,9 @@ pub struct I3BarBlock {
     pub instance: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub urgent: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(skip)]
     pub separator: Option<bool>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(skip)]
     pub separator_block_width: Option<usize>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub markup: Option<String>,
@@ -1,5 +1,4 @@
 use std::os::unix::io::FromRawFd;
-use std::time::Duration;
 
 use serde_derive::Deserialize;
 
@@ -7,23 +6,69 @@ use tokio::fs::File;
 use tokio::io::{AsyncBufReadExt, BufReader};
 use tokio::sync::mpsc::{channel, Receiver};
 
-use crate::click::MouseButton;
+use crate::click::{Modifiers, MouseButton};
 
 #[derive(Deserialize, Debug, Clone)]
 struct I3BarEventInternal {
     pub name: Option<String>,
     pub instance: Option<String>,
     pub button: MouseButton,
+    #[serde(default)]
+    pub modifiers: Modifiers,
+    #[serde(default)]
+    pub x: f64,
+    #[serde(default)]
+    pub y: f64,
+    #[serde(default)]
+    pub relative_x: f64,
+    #[serde(default)]
+    pub relative_y: f64,
+    #[serde(default)]
+    pub width: f64,
+    #[serde(default)]
+    pub height: f64,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+/// A click on a widget. The coordinates are in pixels and may be zero if the bar doesn't send
+/// them.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
 pub struct I3BarEvent {
     pub id: usize,
     pub instance: Option<usize>,
     pub button: MouseButton,
+    /// The number of consecutive clicks, e.g. `2` for a double click
+    pub clicks: u8,
+    pub modifiers: Modifiers,
+    /// The position of the click on the screen
+    pub x: f64,
+    pub y: f64,
+    /// The position of the click relative to the top left corner of the widget
+    pub relative_x: f64,
+    pub relative_y: f64,
+    /// The size of the widget
+    pub width: f64,
+    pub height: f64,
 }
 
-fn unprocessed_events_stream(invert_scrolling: bool) -> Receiver<I3BarEvent> {
+impl I3BarEvent {
+    /// The horizontal position of the click within the widget, from `0.0` (left edge) to `1.0`
+    /// (right edge). `None` if the bar doesn't send the widget's size.
+    pub fn fraction_x(&self) -> Option<f64> {
+        (self.width > 0.0).then(|| (self.relative_x / self.width).clamp(0.0, 1.0))
+    }
+
+    /// Whether two events are clicks of the same button on the same widget, ignoring the position
+    pub fn same_click(&self, other: &Self) -> bool {
+        self.id == other.id
+            && self.instance == other.instance
+            && self.button == other.button
+            && self.modifiers == other.modifiers
+    }
+}
+
+/// Read click events from stdin. Every event is a single click, consecutive clicks are combined by
+/// the bar state, which knows which blocks are interested in them.
+pub fn events_stream(invert_scrolling: bool) -> Receiver<I3BarEvent> {
     // Avoid spawning a blocking therad (why doesn't tokio do this too?)
     // This should be safe given that this function is called only once
     let stdin = unsafe { File::from_raw_fd(0) };
@@ -62,6 +107,14 @@ fn unprocessed_events_stream(invert_scrolling: bool) -> Receiver<I3BarEvent> {
                         id,
                         instance,
                         button,
+                        clicks: 1,
+                        modifiers: event.modifiers,
+                        x: event.x,
+                        y: event.y,
+                        relative_x: event.relative_x,
+                        relative_y: event.relative_y,
+                        width: event.width,
+                        height: event.height,
                     })
                     .await
                     .is_err()
@@ -74,37 +127,3 @@ fn unprocessed_events_stream(invert_scrolling: bool) -> Receiver<I3BarEvent> {
 
     rx
 }
-
-pub fn events_stream(invert_scrolling: bool, double_click_delay: Duration) -> Receiver<I3BarEvent> {
-    let mut events = unprocessed_events_stream(invert_scrolling);
-    let (tx, rx) = channel(32);
-
-    tokio::spawn(async move {
-        loop {
-            let mut event = events.recv().await.unwrap();
-
-            // Handle double clicks (for now only left)
-            if event.button == MouseButton::Left {
-                tokio::select! {
-                    _ = tokio::time::sleep(double_click_delay) => (),
-                    Some(new_event) = events.recv() => {
-                        if event == new_event {
-                            event.button = MouseButton::DoubleLeft;
-                        } else {
-                            if tx.send(event).await.is_err() {
-                                break;
-                            }
-                            event = new_event;
-                        }
-                    }
-                }
-            }
-
-            if tx.send(event).await.is_err() {
-                break;
-            }
-        }
-    });
-
-    rx
-}
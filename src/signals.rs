@@ -1,24 +1,129 @@
 use futures::stream::StreamExt;
 use libc::{SIGRTMAX, SIGRTMIN};
-use signal_hook::consts::{SIGUSR1, SIGUSR2};
+use serde::de::{self, Deserialize, Deserializer, Visitor};
+use signal_hook::consts::{SIGCONT, SIGTSTP, SIGUSR1, SIGUSR2};
 use signal_hook_tokio::Signals;
+use std::fmt;
 use tokio::sync::mpsc;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Signal {
     Usr1,
     Usr2,
+    /// The bar asks us to pause, e.g. because it is hidden
+    Stop,
+    /// The bar is visible again
+    Cont,
     Custom(i32),
 }
 
-/// Spawn a task that listens for signals and sends these on the returned channel
-pub fn signals_stream() -> mpsc::Receiver<Signal> {
+/// The signal the bar sends to pause swaystatus (see [`Signal::Stop`])
+pub const STOP_SIGNAL: i32 = SIGTSTP;
+/// The signal the bar sends to resume swaystatus (see [`Signal::Cont`])
+pub const CONT_SIGNAL: i32 = SIGCONT;
+
+impl Signal {
+    /// Parse signal names like `SIGUSR1`, `usr1`, `SIGRTMIN+4`, `RTMIN`, or `SIGRTMAX-2`.
+    fn from_name(name: &str) -> Option<Self> {
+        let name = name.to_ascii_uppercase();
+        let name = name.strip_prefix("SIG").unwrap_or(&name);
+        match name {
+            "USR1" => return Some(Self::Usr1),
+            "USR2" => return Some(Self::Usr2),
+            _ => (),
+        }
+        let (sigmin, sigmax) = (SIGRTMIN(), SIGRTMAX());
+        let signal = if let Some(offset) = name.strip_prefix("RTMIN") {
+            sigmin + parse_offset(offset, '+')?
+        } else if let Some(offset) = name.strip_prefix("RTMAX") {
+            sigmax - parse_offset(offset, '-')?
+        } else {
+            return None;
+        };
+        Self::custom(signal - sigmin)
+    }
+
+    /// Custom signal with the given offset from `SIGRTMIN`, if it's a valid realtime signal.
+    fn custom(offset: i32) -> Option<Self> {
+        (0..=SIGRTMAX() - SIGRTMIN())
+            .contains(&offset)
+            .then(|| Self::Custom(offset))
+    }
+}
+
+fn parse_offset(offset: &str, sign: char) -> Option<i32> {
+    if offset.is_empty() {
+        Some(0)
+    } else {
+        offset.strip_prefix(sign)?.trim().parse().ok()
+    }
+}
+
+impl<'de> Deserialize<'de> for Signal {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct SignalVisitor;
+
+        impl<'de> Visitor<'de> for SignalVisitor {
+            type Value = Signal;
+
+            fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+                formatter.write_str("a realtime signal offset or a signal name")
+            }
+
+            /// Handle configs like:
+            ///
+            /// ```toml
+            /// signal = 4
+            /// ```
+            fn visit_i64<E>(self, offset: i64) -> Result<Signal, E>
+            where
+                E: de::Error,
+            {
+                i32::try_from(offset)
+                    .ok()
+                    .and_then(Signal::custom)
+                    .ok_or_else(|| {
+                        de::Error::custom(format!("Signal offset {} is out of range", offset))
+                    })
+            }
+
+            /// Handle configs like:
+            ///
+            /// ```toml
+            /// signal = "SIGRTMIN+4"
+            /// ```
+            fn visit_str<E>(self, name: &str) -> Result<Signal, E>
+            where
+                E: de::Error,
+            {
+                Signal::from_name(name)
+                    .ok_or_else(|| de::Error::custom(format!("Unknown signal '{}'", name)))
+            }
+        }
+
+        deserializer.deserialize_any(SignalVisitor)
+    }
+}
+
+/// Spawn a task that listens for signals and sends these on the returned channel. The stop and
+/// cont signals are only handled if `pause_signals` is set, otherwise they keep their default
+/// behavior.
+pub fn signals_stream(pause_signals: bool) -> mpsc::Receiver<Signal> {
     let (tx, rx) = mpsc::channel(32);
 
     let (sigmin, sigmax) = (SIGRTMIN(), SIGRTMAX());
-    let mut signals = Signals::new((sigmin..sigmax).chain(Some(SIGUSR1)).chain(Some(SIGUSR2)))
-        .unwrap()
-        .fuse();
+    let pause_signals = pause_signals.then(|| [STOP_SIGNAL, CONT_SIGNAL]);
+    let mut signals = Signals::new(
+        (sigmin..=sigmax)
+            .chain(Some(SIGUSR1))
+            .chain(Some(SIGUSR2))
+            .chain(pause_signals.into_iter().flatten()),
+    )
+    .unwrap()
+    .fuse();
 
     tokio::spawn(async move {
         loop {
@@ -26,6 +131,8 @@ pub fn signals_stream() -> mpsc::Receiver<Signal> {
                 .send(match signals.next().await {
                     Some(SIGUSR1) => Signal::Usr1,
                     Some(SIGUSR2) => Signal::Usr2,
+                    Some(STOP_SIGNAL) => Signal::Stop,
+                    Some(CONT_SIGNAL) => Signal::Cont,
                     Some(x) => Signal::Custom(x - sigmin),
                     None => {
                         eprintln!("signals.next() returned None: no more signals will be received");
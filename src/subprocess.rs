@@ -5,8 +5,14 @@ use std::thread;
 /// Spawns a new child process. This closes stdin and stdout, and returns to the caller after the
 /// child has been started, while a background thread waits for the child to exit.
 pub fn spawn_shell(cmd: &str) -> io::Result<()> {
+    spawn_shell_with_env(cmd, &[])
+}
+
+/// Like [`spawn_shell`], but with additional environment variables.
+pub fn spawn_shell_with_env(cmd: &str, env: &[(String, String)]) -> io::Result<()> {
     let mut child = Command::new("sh")
         .args(&["-c", cmd])
+        .envs(env.iter().map(|(k, v)| (k, v)))
         .stdin(Stdio::null())
         .stdout(Stdio::null())
         .spawn()?;
@@ -17,8 +23,14 @@ pub fn spawn_shell(cmd: &str) -> io::Result<()> {
 }
 
 pub async fn spawn_shell_sync(cmd: &str) -> io::Result<()> {
+    spawn_shell_sync_with_env(cmd, &[]).await
+}
+
+/// Like [`spawn_shell_sync`], but with additional environment variables.
+pub async fn spawn_shell_sync_with_env(cmd: &str, env: &[(String, String)]) -> io::Result<()> {
     tokio::process::Command::new("sh")
         .args(&["-c", cmd])
+        .envs(env.iter().map(|(k, v)| (k, v)))
         .stdin(Stdio::null())
         .stdout(Stdio::null())
         .spawn()?
@@ -28,16 +28,11 @@ impl Serialize for Color {
     where
         S: Serializer,
     {
-        let format_rgb = |rgb: Rgb, a: u8| {
-            format!(
-                "#{:02X}{:02X}{:02X}{:02X}",
-                rgb.r as u8, rgb.g as u8, rgb.b as u8, a
-            )
-        };
-        match *self {
-            Self::None | Self::Auto => serializer.serialize_none(),
-            Self::Rgba(rgb, a) => serializer.serialize_str(&format_rgb(rgb, a)),
-            Self::Hsva(hsv, a) => serializer.serialize_str(&format_rgb(hsv.into(), a)),
+        match self.rgba() {
+            Some((r, g, b, a)) => {
+                serializer.serialize_str(&format!("#{:02X}{:02X}{:02X}{:02X}", r, g, b, a))
+            }
+            None => serializer.serialize_none(),
         }
     }
 }
@@ -46,6 +41,25 @@ impl Color {
     pub fn skip_ser(&self) -> bool {
         matches!(self, Self::None | Self::Auto)
     }
+
+    /// The red, green, blue and alpha components, if the color is set
+    pub fn rgba(self) -> Option<(u8, u8, u8, u8)> {
+        let (rgb, a) = match self {
+            Self::None | Self::Auto => return None,
+            Self::Rgba(rgb, a) => (rgb, a),
+            Self::Hsva(hsv, a) => (hsv.into(), a),
+        };
+        Some((rgb.r as u8, rgb.g as u8, rgb.b as u8, a))
+    }
+
+    /// The same color at half the opacity
+    pub fn dimmed(self) -> Self {
+        match self {
+            Self::None | Self::Auto => self,
+            Self::Rgba(rgb, a) => Self::Rgba(rgb, a / 2),
+            Self::Hsva(hsv, a) => Self::Hsva(hsv, a / 2),
+        }
+    }
 }
 
 impl Default for Color {
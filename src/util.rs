@@ -5,9 +5,11 @@ use std::io::BufReader;
 use std::path::{Path, PathBuf};
 use std::prelude::v1::String;
 
+use futures::StreamExt;
 use serde::de::DeserializeOwned;
 use tokio::io::AsyncReadExt;
 use tokio::process::Command;
+use tokio::sync::mpsc;
 
 use crate::errors::*;
 
@@ -111,20 +113,53 @@ pub async fn new_system_dbus_connection() -> Result<zbus::Connection> {
     Ok(conn)
 }
 
+#[zbus::dbus_proxy(
+    interface = "org.freedesktop.login1.Manager",
+    default_service = "org.freedesktop.login1",
+    default_path = "/org/freedesktop/login1"
+)]
+trait LogindManager {
+    #[dbus_proxy(signal)]
+    fn prepare_for_sleep(&self, start: bool) -> zbus::Result<()>;
+}
+
+/// Spawn a task that sends a message on the returned channel every time the system wakes up from
+/// suspend or hibernation
+pub async fn resume_stream(conn: &zbus::Connection) -> Result<mpsc::Receiver<()>> {
+    let proxy = LogindManagerProxy::new(conn)
+        .await
+        .error("Failed to create LogindManagerProxy")?;
+    let mut signals = proxy
+        .receive_prepare_for_sleep()
+        .await
+        .error("Failed to receive PrepareForSleep signals")?;
+
+    let (tx, rx) = mpsc::channel(4);
+    tokio::spawn(async move {
+        while let Some(signal) = signals.next().await {
+            // The signal is sent with `start = true` before sleeping and `false` after waking up
+            let resumed = signal.args().map_or(false, |args| !*args.start());
+            if resumed && tx.send(()).await.is_err() {
+                break;
+            }
+        }
+    });
+    Ok(rx)
+}
+
+/// Ordered list of icons used to display battery charge, one per 10%
+pub const BATTERY_LEVEL_ICONS: [&str; 10] = [
+    "bat_10", "bat_20", "bat_30", "bat_40", "bat_50", "bat_60", "bat_70", "bat_80", "bat_90",
+    "bat_full",
+];
+// TODO: use different charging icons
+pub const BATTERY_CHARGING_ICON: &str = "bat_charging";
+
 pub fn battery_level_icon(level: u8, charging: bool) -> &'static str {
-    match (level, charging) {
-        // TODO: use different charging icons
-        (_, true) => "bat_charging",
-        (0..=10, _) => "bat_10",
-        (11..=20, _) => "bat_20",
-        (21..=30, _) => "bat_30",
-        (31..=40, _) => "bat_40",
-        (41..=50, _) => "bat_50",
-        (51..=60, _) => "bat_60",
-        (61..=70, _) => "bat_70",
-        (71..=80, _) => "bat_80",
-        (81..=90, _) => "bat_90",
-        _ => "bat_full",
+    if charging {
+        BATTERY_CHARGING_ICON
+    } else {
+        BATTERY_LEVEL_ICONS[(usize::from(level.saturating_sub(1)) / 10).min(9)]
     }
 }
 
@@ -140,6 +175,12 @@ pub fn xdg_config_home() -> Option<PathBuf> {
         .map(PathBuf::from)
 }
 
+pub fn hostname() -> Result<String> {
+    let mut buf = [0u8; 256];
+    let hostname = nix::unistd::gethostname(&mut buf).error("Failed to get hostname")?;
+    Ok(hostname.to_string_lossy().into_owned())
+}
+
 pub fn deserialize_toml_file<T>(path: &Path) -> Result<T>
 where
     T: DeserializeOwned,
@@ -247,6 +288,17 @@ mod tests {
         assert!(!tokio_test::block_on(has_command("thequickbrownfoxjumpsoverthelazydog")).unwrap());
     }
 
+    #[test]
+    fn test_battery_level_icon() {
+        assert_eq!(battery_level_icon(0, false), "bat_10");
+        assert_eq!(battery_level_icon(10, false), "bat_10");
+        assert_eq!(battery_level_icon(11, false), "bat_20");
+        assert_eq!(battery_level_icon(90, false), "bat_90");
+        assert_eq!(battery_level_icon(91, false), "bat_full");
+        assert_eq!(battery_level_icon(100, false), "bat_full");
+        assert_eq!(battery_level_icon(50, true), "bat_charging");
+    }
+
     #[test]
     fn test_flags() {
         assert!(country_flag_from_iso_code("ES") == "🇪🇸");
@@ -42,11 +42,11 @@ enum Source {
 }
 
 impl Source {
-    fn render(&self) -> Result<(String, Option<String>)> {
+    fn render(&self, config: &SharedConfig) -> Result<(String, Option<String>)> {
         match self {
             Source::Text(text) => Ok((text.clone(), None)),
             Source::TextWithShort(full, short) => Ok((full.clone(), Some(short.clone()))),
-            Source::Format(format, Some(values)) => format.render(values),
+            Source::Format(format, Some(values)) => format.render(values, config),
             Source::Format(_, None) => Ok((String::new(), None)),
         }
     }
@@ -55,6 +55,7 @@ impl Source {
 #[derive(Debug)]
 pub struct Widget {
     instance: Option<usize>,
+    /// The name of the icon. It is resolved using `shared_config` at render time.
     pub icon: String,
     pub shared_config: SharedConfig,
     pub state: State,
@@ -120,15 +121,19 @@ impl Widget {
         self.source = Source::TextWithShort(short, full);
     }
 
-    pub fn set_format(&mut self, format: RunningFormat) {
+    pub fn set_format(&mut self, mut format: RunningFormat) {
         match &mut self.source {
-            Source::Format(old, _) => *old = format,
+            Source::Format(old, _) => {
+                format.inherit_history(old);
+                *old = format;
+            }
             _ => self.source = Source::Format(format, None),
         }
     }
 
     pub fn set_values(&mut self, new_values: Values) {
-        if let Source::Format(_, values) = &mut self.source {
+        if let Source::Format(format, values) = &mut self.source {
+            format.push_history(&new_values);
             *values = Some(new_values);
         }
     }
@@ -141,6 +146,14 @@ impl Widget {
         self.instance
     }
 
+    /// The values of the format, if the widget is formatted
+    pub fn get_values(&self) -> Option<&Values> {
+        match &self.source {
+            Source::Format(_, values) => values.as_ref(),
+            _ => None,
+        }
+    }
+
     /*
      * Preserve / Restore
      */
@@ -161,13 +174,24 @@ impl Widget {
 
     /// Constuct `I3BarBlock` from this widget
     pub fn get_data(&self) -> Result<I3BarBlock> {
+        self.get_data_with(&self.shared_config)
+    }
+
+    /// Constuct `I3BarBlock` from this widget as if it had `shared_config`
+    pub fn get_data_with(&self, shared_config: &SharedConfig) -> Result<I3BarBlock> {
         let mut data = self.inner.clone();
 
-        let (key_bg, key_fg) = self.shared_config.theme.get_colors(self.state);
+        let (key_bg, key_fg) = shared_config.theme.get_colors(self.state);
         data.background = key_bg;
         data.color = key_fg;
 
-        let (full, short) = self.source.render()?;
+        let icon = if self.icon.is_empty() {
+            String::new()
+        } else {
+            shared_config.get_icon(&self.icon)?
+        };
+
+        let (full, short) = self.source.render(shared_config)?;
         let full_spacing = if full.is_empty() {
             Spacing::Hidden
         } else {
@@ -181,7 +205,7 @@ impl Widget {
 
         data.full_text = format!(
             "{}{}{}",
-            match (self.icon.as_str(), full_spacing) {
+            match (icon.as_str(), full_spacing) {
                 ("", Spacing::Normal) => " ",
                 ("", Spacing::Hidden) => "",
                 (icon, _) => icon,
@@ -196,7 +220,7 @@ impl Widget {
         data.short_text = short.as_ref().map(|short_text| {
             format!(
                 "{}{}{}",
-                match (self.icon.as_str(), short_spacing) {
+                match (icon.as_str(), short_spacing) {
                     ("", Spacing::Normal) => " ",
                     ("", Spacing::Hidden) => "",
                     (icon, _) => icon,
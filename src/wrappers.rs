@@ -1,6 +1,9 @@
 use serde::de::{self, Deserialize, Deserializer};
 use std::borrow::Cow;
 use std::time::Duration;
+use tokio::time::Instant;
+
+use crate::power;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum OnceDuration {
@@ -74,10 +77,30 @@ impl Seconds {
         Self(Duration::from_secs(value))
     }
 
-    pub fn timer(self) -> tokio::time::Interval {
-        let mut timer = tokio::time::interval_at(tokio::time::Instant::now() + self.0, self.0);
-        timer.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
-        timer
+    /// A timer which ticks every `self` seconds, or less often while on battery (see
+    /// `interval_on_battery`)
+    pub fn timer(self) -> Timer {
+        Timer {
+            period: self.0,
+            last: Instant::now(),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Timer {
+    period: Duration,
+    last: Instant,
+}
+
+impl Timer {
+    /// Wait for the next tick. The first tick completes after one period, and missed ticks are
+    /// not caught up. While the bar is paused, the tick is delayed until it resumes.
+    pub async fn tick(&mut self) -> Instant {
+        power::sleep_since(self.last, self.period).await;
+        crate::wait_until_resumed().await;
+        self.last = Instant::now();
+        self.last
     }
 }
 